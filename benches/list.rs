@@ -21,15 +21,17 @@ pub fn bench_segmented_list(c: &mut Criterion) {
     bench_push(c, "segmented_list_push_u64", 123u64, 10_000);
 
     #[derive(Clone)]
+    #[allow(dead_code)]
     struct MediumElem([u8; 40]);
     bench_push(c, "segmented_list_push_medium", MediumElem([42; 40]), 1_000);
 
     #[derive(Clone)]
+    #[allow(dead_code)]
     struct HeavyElem(Box<[u8]>);
     bench_push(
         c,
         "segmented_list_push_heavy_1MiB",
-        HeavyElem(vec![161u8; 1 * 1024 * 1024].into_boxed_slice()),
+        HeavyElem(vec![161u8; 1024 * 1024].into_boxed_slice()),
         10,
     );
 
@@ -46,7 +48,87 @@ pub fn bench_segmented_list(c: &mut Criterion) {
         HeavyElem(vec![161u8; 50 * 1024 * 1024].into_boxed_slice()),
         1,
     );
+
+    let list = (0..10_000u64).collect::<SegmentedList<_>>();
+    c.bench_function("segmented_list_iter_u64", |b| {
+        b.iter(|| {
+            let mut sum = 0u64;
+            for v in list.iter() {
+                sum = sum.wrapping_add(*v);
+            }
+            black_box(sum)
+        })
+    });
+
+    let slice = (0..10_000u64).collect::<Vec<_>>();
+    c.bench_function("segmented_list_extend_u64", |b| {
+        b.iter_batched(
+            SegmentedList::new,
+            |mut list| {
+                list.extend(black_box(&slice).iter().copied());
+                black_box(list)
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    c.bench_function("segmented_list_extend_from_slice_clone_u64", |b| {
+        b.iter_batched(
+            SegmentedList::new,
+            |mut list| {
+                list.extend_from_slice_clone(black_box(&slice));
+                black_box(list)
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    c.bench_function("segmented_list_push_slice_u64", |b| {
+        b.iter_batched(
+            SegmentedList::new,
+            |mut list| {
+                list.push_slice(black_box(&slice));
+                black_box(list)
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+pub fn bench_segmented_list_bulk(c: &mut Criterion) {
+    let slice = (0..10_000_000u64).collect::<Vec<_>>();
+    let mut group = c.benchmark_group("segmented_list_10m_u64");
+    group.sample_size(10);
+    group.bench_function("extend", |b| {
+        b.iter_batched(
+            SegmentedList::new,
+            |mut list| {
+                list.extend(black_box(&slice).iter().copied());
+                black_box(list)
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("extend_from_slice", |b| {
+        b.iter_batched(
+            SegmentedList::new,
+            |mut list| {
+                list.extend_from_slice(black_box(&slice));
+                black_box(list)
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("extend_from_slice_clone", |b| {
+        b.iter_batched(
+            SegmentedList::new,
+            |mut list| {
+                list.extend_from_slice_clone(black_box(&slice));
+                black_box(list)
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
 }
 
-criterion_group!(benches, bench_segmented_list);
+criterion_group!(benches, bench_segmented_list, bench_segmented_list_bulk);
 criterion_main!(benches);
@@ -20,15 +20,17 @@ pub fn bench_vec(c: &mut Criterion) {
     bench_push(c, "vec_push_u64", 123u64, 10_000);
 
     #[derive(Clone)]
+    #[allow(dead_code)]
     struct MediumElem([u8; 40]);
     bench_push(c, "vec_push_medium", MediumElem([42; 40]), 1_000);
 
     #[derive(Clone)]
+    #[allow(dead_code)]
     struct HeavyElem(Box<[u8]>);
     bench_push(
         c,
         "vec_push_heavy_1MiB",
-        HeavyElem(vec![161u8; 1 * 1024 * 1024].into_boxed_slice()),
+        HeavyElem(vec![161u8; 1024 * 1024].into_boxed_slice()),
         10,
     );
 
@@ -45,6 +47,17 @@ pub fn bench_vec(c: &mut Criterion) {
         HeavyElem(vec![161u8; 50 * 1024 * 1024].into_boxed_slice()),
         1,
     );
+
+    let vec = (0..10_000u64).collect::<Vec<_>>();
+    c.bench_function("vec_iter_u64", |b| {
+        b.iter(|| {
+            let mut sum = 0u64;
+            for v in vec.iter() {
+                sum = sum.wrapping_add(*v);
+            }
+            black_box(sum)
+        })
+    });
 }
 
 criterion_group!(benches, bench_vec);
@@ -128,6 +128,33 @@ impl SegmentedAlloc {
         }
     }
 
+    /// Rewinds the allocator to `ptr`, releasing the allocation starting at `ptr` and every
+    /// allocation requested after it. The blocks following the one holding `ptr` are unmapped.
+    /// Panics if `ptr` wasn't handed out by self
+    pub fn release_from(&mut self, ptr: NonNull<u8>) {
+        let ctx = self.ctx.get_mut();
+        let addr = ptr.as_ptr() as usize;
+        let block = (0..=ctx.cur_block)
+            .find(|&i| {
+                ctx.blocks[i].is_some_and(|start| {
+                    let start = start.as_ptr() as usize;
+                    (start..start + ctx.block_sizes[i]).contains(&addr)
+                })
+            })
+            .expect("Pointer was not allocated by this allocator");
+
+        for i in block + 1..=ctx.cur_block {
+            if let Some(released) = ctx.blocks[i].take() {
+                munmap(released, ctx.block_sizes[i]);
+            }
+            ctx.block_sizes[i] = 0;
+        }
+
+        ctx.cur_block = block;
+        ctx.size = ctx.block_sizes[block];
+        ctx.pos = addr - ctx.blocks[block].unwrap().as_ptr() as usize;
+    }
+
     pub fn free(&mut self) {
         let ctx = unsafe { &mut *self.ctx.get() };
         for i in 0..MAX_BLOCKS {
@@ -144,6 +171,12 @@ impl SegmentedAlloc {
     }
 }
 
+impl Default for SegmentedAlloc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Drop for SegmentedAlloc {
     fn drop(&mut self) {
         self.free();
@@ -277,4 +310,28 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn release_from_rewinds_and_unmaps() {
+        let mut alloc = SegmentedAlloc::new();
+        let small = alloc.request(Layout::from_size_align(64, 8).unwrap());
+        let released = alloc.request(Layout::from_size_align(64, 8).unwrap());
+        for _ in 0..4 {
+            alloc.request(Layout::from_size_align(MIN_SIZE * 2, 8).unwrap());
+        }
+        assert!(unsafe { &*alloc.ctx.get() }.cur_block > 0);
+
+        alloc.release_from(released);
+        let ctx = unsafe { &*alloc.ctx.get() };
+        assert_eq!(ctx.cur_block, 0);
+        assert!(ctx.blocks[1..].iter().all(Option::is_none));
+        assert!(ctx.block_sizes[1..].iter().all(|&size| size == 0));
+
+        // the released memory is handed out again, the allocation before it is untouched
+        assert_eq!(
+            alloc.request(Layout::from_size_align(64, 8).unwrap()),
+            released
+        );
+        assert_ne!(small, released);
+    }
 }
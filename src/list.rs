@@ -12,15 +12,47 @@ pub const BLOCK_STARTS: [usize; BLOCK_COUNT] = {
     }
     arr
 };
+/// Hard ceiling of elements a list can hold, the sum of all `BLOCK_COUNT` block sizes
+pub const MAX_CAPACITY: usize = BLOCK_STARTS[BLOCK_COUNT - 1] + (START_SIZE << (BLOCK_COUNT - 1));
 
 /// SegmentedIdx represents a cached index lookup into the segmented list, computed with
 /// `SegmentedList::compute_segmented_idx`, can be used with `SegmentedList::get_with_segmented_idx`
-/// and `SegmentedList::get_mut_with_segmented_idx`.
+/// and `SegmentedList::get_mut_with_segmented_idx`, or their checked variants.
 ///
 /// Primary usecase is to cache the lookup of many idxes, thus omiting the lookup computation which
 /// can be too heavy in intensive workloads.
-#[derive(Copy, Clone)]
-struct SegmentedIdx(usize, usize);
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SegmentedIdx(usize, usize);
+
+/// CapacityError is returned by `SegmentedList::try_push` if the list already holds
+/// `MAX_CAPACITY` elements, it hands the rejected element back.
+#[derive(Clone, PartialEq, Eq)]
+pub struct CapacityError<T>(pub T);
+
+impl<T> CapacityError<T> {
+    /// Returns the element that didn't fit
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> std::fmt::Debug for CapacityError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CapacityError")
+    }
+}
+
+impl<T> std::fmt::Display for CapacityError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "SegmentedList is full, it holds at most {} elements",
+            MAX_CAPACITY
+        )
+    }
+}
+
+impl<T> std::error::Error for CapacityError<T> {}
 
 /// SegmentedList is a drop in `std::vec::Vec` replacement providing zero cost growing and stable
 /// pointers even after grow with `::push`.
@@ -28,8 +60,9 @@ struct SegmentedIdx(usize, usize);
 /// The list is implemented by chaining blocks of memory to store its elements. Each block is
 /// allocated on demand when an index falls into it (for instance during appends), starting at
 /// `START_SIZE` elements in the first block and doubling the block size for each subsequent
-/// allocation. This continues until `BLOCK_COUNT` is reached. Existing blocks are never moved or
-/// reallocated, so references into the list remain valid across growth operations.
+/// allocation. This continues until `BLOCK_COUNT` is reached, capping the list at `MAX_CAPACITY`
+/// elements. Existing blocks are never moved or reallocated, so references into the list remain
+/// valid across growth operations.
 ///
 /// This makes the SegmentedList an adequate replacement for `std::vec::Vec` when dealing with
 /// heavy and unpredictable growth workloads due the omission of copy/move overhead on expansion.
@@ -44,6 +77,8 @@ pub struct SegmentedList<T> {
 
 impl<T> Drop for SegmentedList<T> {
     fn drop(&mut self) {
+        // run the destructors of all initialized elements before the arena goes away
+        self.clear();
         self.allocator.free()
     }
 }
@@ -69,17 +104,216 @@ impl<T> SegmentedList<T> {
         s
     }
 
+    /// Creates an empty list with all blocks needed to hold `n` elements already allocated, so
+    /// the first `n` pushes don't allocate
+    pub fn with_capacity(n: usize) -> Self {
+        let mut s = Self::new();
+        s.grow_to(n);
+        s
+    }
+
     #[inline(always)]
     fn idx_to_block_idx(&self, idx: usize) -> SegmentedIdx {
         if idx < START_SIZE {
             return SegmentedIdx(0, idx);
         }
         let adjusted = idx + START_SIZE;
-        let msb_pos = core::mem::size_of::<usize>() * 8 - 1 - adjusted.leading_zeros() as usize;
+        // usize::BITS keeps this correct on 32 bit targets
+        let msb_pos = (usize::BITS - 1 - adjusted.leading_zeros()) as usize;
         let block = msb_pos - (START_SIZE.trailing_zeros() as usize);
         SegmentedIdx(block, idx - BLOCK_STARTS[block])
     }
 
+    /// Returns a pointer to the slot of `idx`, the caller has to ensure its block is allocated
+    #[inline(always)]
+    fn slot(&self, idx: usize) -> *mut MaybeUninit<T> {
+        let SegmentedIdx(block, block_index) = self.idx_to_block_idx(idx);
+        unsafe { self.blocks[block].add(block_index) }
+    }
+
+    /// Returns the initialized elements of `block` as a slice
+    #[inline(always)]
+    fn block_slice(&self, block: usize) -> &[T] {
+        let used = self
+            .len
+            .saturating_sub(BLOCK_STARTS[block])
+            .min(self.block_lengths[block]);
+        if used == 0 {
+            return &[];
+        }
+        unsafe { std::slice::from_raw_parts(self.blocks[block] as *const T, used) }
+    }
+
+    /// Returns the elements of self as contiguous slices, one per block holding elements, in
+    /// order. The last slice only covers the initialized prefix of its block. Allocated blocks
+    /// without elements are skipped, thus an empty list yields no slices at all
+    #[inline]
+    pub fn segments(&self) -> impl Iterator<Item = &[T]> {
+        (0..BLOCK_COUNT)
+            .take_while(|&block| BLOCK_STARTS[block] < self.len)
+            .map(|block| self.block_slice(block))
+    }
+
+    /// Same as `::segments`
+    pub fn as_flat_slices(&self) -> impl Iterator<Item = &[T]> {
+        self.segments()
+    }
+
+    /// Same as `::segments`, an empty list yields no slices rather than one empty slice
+    pub fn as_segments(&self) -> impl Iterator<Item = &[T]> {
+        self.segments()
+    }
+
+    /// Returns the elements of self as mutable contiguous slices, one per block holding elements,
+    /// see `::as_segments`. The blocks never overlap, thus the slices can be held at the same time
+    pub fn as_segments_mut(&mut self) -> impl Iterator<Item = &mut [T]> {
+        let (blocks, block_lengths, len) = (self.blocks, self.block_lengths, self.len);
+        (0..BLOCK_COUNT)
+            .take_while(move |&block| BLOCK_STARTS[block] < len)
+            .map(move |block| {
+                let used = (len - BLOCK_STARTS[block]).min(block_lengths[block]);
+                // the slices borrow self mutably, so the list can't be touched while they live
+                unsafe { std::slice::from_raw_parts_mut(blocks[block] as *mut T, used) }
+            })
+    }
+
+    /// Sets the length of self and moves the push cursor onto the slot following the last
+    /// element, doesn't drop or initialize anything
+    #[inline(always)]
+    fn set_len(&mut self, len: usize) {
+        self.len = len;
+        if len == MAX_CAPACITY {
+            // there is no slot following the last one, ::try_push checks for this block
+            self.cur_block = BLOCK_COUNT;
+            self.offset_in_block = 0;
+            return;
+        }
+        let SegmentedIdx(block, block_index) = self.idx_to_block_idx(len);
+        self.cur_block = block;
+        self.offset_in_block = block_index;
+    }
+
+    /// Moves `count` elements starting at `src` to `dst`, one `std::ptr::copy` per run of
+    /// elements that doesn't cross a block boundary on either side. Handles overlapping ranges
+    /// like `std::ptr::copy`. The caller has to ensure all destination slots are allocated and
+    /// that the moved out source slots are treated as uninitialized afterwards.
+    unsafe fn shift(&mut self, src: usize, dst: usize, count: usize) {
+        let mut moved = 0;
+        if dst < src {
+            // front to back, so no element is overwritten before it has been moved
+            while moved < count {
+                let SegmentedIdx(src_block, src_index) = self.idx_to_block_idx(src + moved);
+                let SegmentedIdx(dst_block, dst_index) = self.idx_to_block_idx(dst + moved);
+                let n = (self.block_lengths[src_block] - src_index)
+                    .min(self.block_lengths[dst_block] - dst_index)
+                    .min(count - moved);
+                unsafe {
+                    std::ptr::copy(
+                        self.blocks[src_block].add(src_index),
+                        self.blocks[dst_block].add(dst_index),
+                        n,
+                    )
+                };
+                moved += n;
+            }
+        } else if dst > src {
+            // back to front, runs end at the last element not yet moved
+            while moved < count {
+                let last = count - moved - 1;
+                let SegmentedIdx(src_block, src_index) = self.idx_to_block_idx(src + last);
+                let SegmentedIdx(dst_block, dst_index) = self.idx_to_block_idx(dst + last);
+                let n = (src_index + 1).min(dst_index + 1).min(count - moved);
+                unsafe {
+                    std::ptr::copy(
+                        self.blocks[src_block].add(src_index + 1 - n),
+                        self.blocks[dst_block].add(dst_index + 1 - n),
+                        n,
+                    )
+                };
+                moved += n;
+            }
+        }
+    }
+
+    /// Moves all elements into a temporary `Vec`, calls `f` on it and moves them back block by
+    /// block, also if `f` panics
+    fn with_contiguous<R>(&mut self, f: impl FnOnce(&mut [T]) -> R) -> R {
+        /// Moves the scratch elements back into the list once dropped
+        struct MoveBack<'a, T> {
+            list: &'a mut SegmentedList<T>,
+            scratch: Vec<T>,
+        }
+
+        impl<T> Drop for MoveBack<'_, T> {
+            fn drop(&mut self) {
+                let len = self.scratch.len();
+                unsafe {
+                    self.scratch.set_len(0);
+                    self.list.append_raw(self.scratch.as_ptr(), len);
+                }
+            }
+        }
+
+        let mut scratch: Vec<T> = Vec::with_capacity(self.len);
+        for elements in self.segments() {
+            unsafe {
+                std::ptr::copy_nonoverlapping(
+                    elements.as_ptr(),
+                    scratch.as_mut_ptr().add(scratch.len()),
+                    elements.len(),
+                );
+                scratch.set_len(scratch.len() + elements.len());
+            }
+        }
+        // the elements belong to scratch until they are moved back
+        self.set_len(0);
+
+        let mut guard = MoveBack {
+            list: self,
+            scratch,
+        };
+        f(&mut guard.scratch)
+    }
+
+    /// Sorts self in place with heapsort, swapping the elements through their slots
+    fn heapsort_by<F: FnMut(&T, &T) -> std::cmp::Ordering>(&mut self, mut compare: F) {
+        let list = &*self;
+        let mut is_less = |a: usize, b: usize| unsafe {
+            compare(
+                (*list.slot(a)).assume_init_ref(),
+                (*list.slot(b)).assume_init_ref(),
+            ) == std::cmp::Ordering::Less
+        };
+        let swap = |a: usize, b: usize| unsafe { std::ptr::swap(list.slot(a), list.slot(b)) };
+
+        // moves node down until both of its children are not greater, heap covers 0..end
+        let mut sift_down = |mut node: usize, end: usize| {
+            loop {
+                let mut child = 2 * node + 1;
+                if child >= end {
+                    break;
+                }
+                if child + 1 < end && is_less(child, child + 1) {
+                    child += 1;
+                }
+                if !is_less(node, child) {
+                    break;
+                }
+                swap(node, child);
+                node = child;
+            }
+        };
+
+        let len = list.len;
+        for node in (0..len / 2).rev() {
+            sift_down(node, len);
+        }
+        for end in (1..len).rev() {
+            swap(0, end);
+            sift_down(0, end);
+        }
+    }
+
     #[inline(always)]
     fn alloc_block(&mut self, block: usize) {
         use std::alloc::Layout;
@@ -97,7 +331,56 @@ impl<T> SegmentedList<T> {
         self.block_lengths[block] = elems;
     }
 
+    /// Allocates every block up to and including the one holding the slot `cap - 1`, so at least
+    /// `cap` elements fit without allocating
+    fn grow_to(&mut self, cap: usize) {
+        if cap == 0 {
+            return;
+        }
+        assert!(cap <= MAX_CAPACITY, "capacity overflow");
+        let SegmentedIdx(last, _) = self.idx_to_block_idx(cap - 1);
+        for block in 0..=last {
+            if self.block_lengths[block] == 0 {
+                self.alloc_block(block);
+            }
+        }
+    }
+
+    /// Moves `count` elements starting at `src` onto the end of self, one `copy_nonoverlapping`
+    /// per block. The caller has to ensure `src` is valid for `count` reads and that the source
+    /// elements are treated as moved out afterwards.
+    unsafe fn append_raw(&mut self, src: *const T, count: usize) {
+        self.grow_to(self.len + count);
+        let mut copied = 0;
+        while copied < count {
+            let SegmentedIdx(block, block_index) = self.idx_to_block_idx(self.len);
+            let n = (self.block_lengths[block] - block_index).min(count - copied);
+            unsafe {
+                std::ptr::copy_nonoverlapping(
+                    src.add(copied),
+                    self.blocks[block].add(block_index) as *mut T,
+                    n,
+                )
+            };
+            copied += n;
+            self.set_len(self.len + n);
+        }
+    }
+
+    /// Appends `v`, panics if self already holds `MAX_CAPACITY` elements. See `::try_push`
+    #[inline(always)]
     pub fn push(&mut self, v: T) {
+        if let Err(err) = self.try_push(v) {
+            panic!("{}", err);
+        }
+    }
+
+    /// Appends `v`, or hands it back in a `CapacityError` if self already holds `MAX_CAPACITY`
+    /// elements, that is once all `BLOCK_COUNT` blocks are full
+    pub fn try_push(&mut self, v: T) -> Result<(), CapacityError<T>> {
+        if self.cur_block == BLOCK_COUNT {
+            return Err(CapacityError(v));
+        }
         if self.block_lengths[self.cur_block] == 0 {
             self.alloc_block(self.cur_block);
         }
@@ -113,6 +396,48 @@ impl<T> SegmentedList<T> {
             self.cur_block += 1;
             self.offset_in_block = 0;
         }
+        Ok(())
+    }
+
+    /// Removes the last element and returns it, or `None` if self is empty. The slot is only
+    /// logically released, its block stays allocated and is reused by the next `::push`
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.set_len(self.len - 1);
+        Some(unsafe { (*self.slot(self.len)).assume_init_read() })
+    }
+
+    /// Inserts `v` at `idx`, shifting all elements after it one slot to the right. This is O(n),
+    /// the elements are moved with one copy per block they span. Panics if `idx > len`
+    pub fn insert(&mut self, idx: usize, v: T) {
+        if idx > self.len {
+            panic!(
+                "insertion index (is {}) should be <= len (is {})",
+                idx, self.len
+            );
+        }
+
+        if idx == self.len {
+            self.push(v);
+            return;
+        }
+
+        if self.cur_block == BLOCK_COUNT {
+            panic!("{}", CapacityError(v));
+        }
+        // the cursor points at the slot the last element moves into
+        if self.block_lengths[self.cur_block] == 0 {
+            self.alloc_block(self.cur_block);
+        }
+
+        unsafe {
+            self.shift(idx, idx + 1, self.len - idx);
+            (*self.slot(idx)).write(v);
+        }
+        self.set_len(self.len + 1);
     }
 
     pub fn get(&self, idx: usize) -> Option<&T> {
@@ -131,125 +456,329 @@ impl<T> SegmentedList<T> {
         Some(unsafe { (*self.blocks[block].add(block_index)).assume_init_mut() })
     }
 
+    /// Returns mutable references to the elements at all of `indices` at once, or `None` if any
+    /// index is out of bounds or appears more than once
+    pub fn get_disjoint_mut<const N: usize>(&mut self, indices: [usize; N]) -> Option<[&mut T; N]> {
+        for (i, &idx) in indices.iter().enumerate() {
+            if idx >= self.len || indices[..i].contains(&idx) {
+                return None;
+            }
+        }
+        // the indices are distinct and in bounds, so the references never alias
+        Some(indices.map(|idx| unsafe { (*self.slot(idx)).assume_init_mut() }))
+    }
+
+    /// Computes the `SegmentedIdx` of `idx` for repeated lookups via `::get_with_segmented_idx`,
+    /// returns `None` if `idx >= len`
+    pub fn compute_segmented_idx(&self, idx: usize) -> Option<SegmentedIdx> {
+        if idx >= self.len {
+            return None;
+        }
+        Some(self.idx_to_block_idx(idx))
+    }
+
     /// Uses precomputed `SegmentedIdx` to return a reference to the element at `idx`
-    fn get_with_segmented_idx(&self, idx: SegmentedIdx) -> Option<&T> {
+    ///
+    /// # Safety
+    ///
+    /// `idx` has to point below the current `len`, for instance if the list didn't shrink since
+    /// it was computed. See `::get_checked_with_segmented_idx` for the checked variant
+    pub unsafe fn get_with_segmented_idx(&self, idx: SegmentedIdx) -> Option<&T> {
         let SegmentedIdx(block, block_index) = idx;
         Some(unsafe { (*self.blocks[block].add(block_index)).assume_init_ref() })
     }
 
     /// Uses precomputed `SegmentedIdx` to return a mutable reference to the element at `idx`
-    fn get_mut_with_segmented_idx(&mut self, idx: SegmentedIdx) -> Option<&mut T> {
+    ///
+    /// # Safety
+    ///
+    /// See `::get_with_segmented_idx`
+    pub unsafe fn get_mut_with_segmented_idx(&mut self, idx: SegmentedIdx) -> Option<&mut T> {
         let SegmentedIdx(block, block_index) = idx;
         Some(unsafe { (*self.blocks[block].add(block_index)).assume_init_mut() })
     }
 
-    /// Returns the length of self
-    pub fn len(&self) -> usize {
-        self.len
+    /// Like `::get_with_segmented_idx`, but returns `None` if `idx` is no longer valid
+    pub fn get_checked_with_segmented_idx(&self, idx: SegmentedIdx) -> Option<&T> {
+        let SegmentedIdx(block, block_index) = idx;
+        if block_index >= self.block_lengths[block] || BLOCK_STARTS[block] + block_index >= self.len
+        {
+            return None;
+        }
+        unsafe { self.get_with_segmented_idx(idx) }
     }
 
-    /// Collects self and its contents into a vec
-    pub fn to_vec(mut self) -> Vec<T> {
-        let mut result = Vec::with_capacity(self.len);
-        let mut remaining = self.len;
+    /// Like `::get_mut_with_segmented_idx`, but returns `None` if `idx` is no longer valid
+    pub fn get_checked_mut_with_segmented_idx(&mut self, idx: SegmentedIdx) -> Option<&mut T> {
+        let SegmentedIdx(block, block_index) = idx;
+        if block_index >= self.block_lengths[block] || BLOCK_STARTS[block] + block_index >= self.len
+        {
+            return None;
+        }
+        unsafe { self.get_mut_with_segmented_idx(idx) }
+    }
 
-        for block_idx in 0..BLOCK_COUNT {
-            if remaining == 0 {
-                break;
-            }
+    /// Appends all elements of `slice`, copying them into the blocks with one memcpy per block
+    /// instead of pushing them one by one. Missing blocks are allocated up front
+    pub fn push_slice(&mut self, slice: &[T])
+    where
+        T: Copy,
+    {
+        unsafe { self.append_raw(slice.as_ptr(), slice.len()) };
+    }
 
-            let len = self.block_lengths[block_idx];
-            if len == 0 {
-                break;
-            }
+    /// Appends all elements of `slice` with one memcpy per block, same as `::push_slice`. Elements
+    /// that aren't `Copy` are appended via `::extend_from_slice_clone`
+    pub fn extend_from_slice(&mut self, slice: &[T])
+    where
+        T: Copy,
+    {
+        self.push_slice(slice);
+    }
 
-            let ptr = self.blocks[block_idx];
-            let take = remaining.min(len);
-            for i in 0..take {
-                let value = unsafe { (*ptr.add(i)).assume_init_read() };
-                result.push(value);
-            }
-            remaining -= take;
-            // We "forget" the block, no dealloc, bump allocator manages memory
-            self.blocks[block_idx] = std::ptr::null_mut();
+    /// Appends clones of all elements of `slice`, allocating the missing blocks up front
+    pub fn extend_from_slice_clone(&mut self, slice: &[T])
+    where
+        T: Clone,
+    {
+        self.reserve(slice.len());
+        for v in slice {
+            self.push(v.clone());
         }
-        result
     }
 
-    pub fn capacity(&self) -> usize {
-        self.block_lengths.iter().copied().sum()
-    }
+    /// Overwrites the first `src.len()` elements with the ones of `src`, one memcpy per block.
+    /// Panics if `src` is longer than self
+    pub fn copy_from_slice(&mut self, src: &[T])
+    where
+        T: Copy,
+    {
+        if src.len() > self.len {
+            panic!(
+                "source slice length ({}) exceeds list length ({})",
+                src.len(),
+                self.len
+            );
+        }
 
-    pub fn is_empty(&self) -> bool {
-        self.len == 0
+        let mut copied = 0;
+        for block in (0..BLOCK_COUNT).take_while(|&block| BLOCK_STARTS[block] < src.len()) {
+            let n = (src.len() - copied).min(self.block_lengths[block]);
+            unsafe {
+                std::ptr::copy_nonoverlapping(
+                    src.as_ptr().add(copied),
+                    self.blocks[block] as *mut T,
+                    n,
+                )
+            };
+            copied += n;
+        }
     }
 
-    pub fn first(&self) -> Option<&T> {
-        if self.len == 0 {
-            None
-        } else {
-            // first element is always at idx 0 of block 0, thus we hardcode this
-            self.get_with_segmented_idx(SegmentedIdx(0, 0))
+    /// Moves all elements of `other` onto the end of self, leaving `other` empty. The missing
+    /// blocks of self are allocated up front, then the elements are copied block by block,
+    /// `other` keeps its blocks for reuse
+    pub fn append(&mut self, other: &mut SegmentedList<T>) {
+        let len = other.len;
+        // growing may panic on overflow, both lists are still intact at this point
+        self.reserve(len);
+        other.set_len(0);
+        for block in (0..BLOCK_COUNT).take_while(|&block| BLOCK_STARTS[block] < len) {
+            let used = (len - BLOCK_STARTS[block]).min(other.block_lengths[block]);
+            unsafe { self.append_raw(other.blocks[block] as *const T, used) };
         }
     }
 
-    pub fn first_mut(&mut self) -> Option<&mut T> {
-        if self.len == 0 {
-            None
-        } else {
-            self.get_mut_with_segmented_idx(SegmentedIdx(0, 0))
+    /// Moves the elements `at..len` into a new list with its own arena and keeps `0..at` in
+    /// self, copying block by block. Panics if `at > len`
+    pub fn split_off(&mut self, at: usize) -> SegmentedList<T> {
+        if at > self.len {
+            panic!(
+                "`at` split index (is {}) should be <= len (is {})",
+                at, self.len
+            );
         }
+
+        let len = self.len;
+        let mut other = SegmentedList::with_capacity(len - at);
+        // other owns the tail from here on, a panic leaks instead of double dropping it
+        self.set_len(at);
+        let mut moved = at;
+        while moved < len {
+            let SegmentedIdx(block, block_index) = self.idx_to_block_idx(moved);
+            let n = (self.block_lengths[block] - block_index).min(len - moved);
+            unsafe { other.append_raw(self.blocks[block].add(block_index) as *const T, n) };
+            moved += n;
+        }
+        other
     }
 
-    pub fn last(&self) -> Option<&T> {
-        if self.len == 0 {
-            None
-        } else {
-            let si = self.idx_to_block_idx(self.len - 1);
-            self.get_with_segmented_idx(si)
+    /// Removes the elements in `range` and returns them as an iterator. Once the iterator is
+    /// dropped, the unconsumed elements of the range are dropped and the tail is shifted into the
+    /// gap. Panics if the range is out of bounds. Leaking the iterator leaks the tail as well
+    pub fn drain<R: std::ops::RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, T> {
+        let std::ops::Range { start, end } = to_range(range, self.len);
+        let tail_len = self.len - end;
+        // the drained range and the tail are owned by the iterator until it is dropped
+        self.set_len(start);
+        Drain {
+            list: self,
+            next: start,
+            end,
+            tail_start: end,
+            tail_len,
         }
     }
 
-    pub fn last_mut(&mut self) -> Option<&mut T> {
-        if self.len == 0 {
-            None
-        } else {
-            let si = self.idx_to_block_idx(self.len - 1);
-            self.get_mut_with_segmented_idx(si)
+    /// Replaces the elements in `range` with `replace_with` and returns the removed elements as an
+    /// iterator, like `::drain`. `replace_with` is only consumed once the iterator is dropped, it
+    /// first fills the gap of the removed range, any further elements are collected into a
+    /// temporary `Vec` and moved in after shifting the tail back. Panics if the range is out of
+    /// bounds
+    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> Splice<'_, I::IntoIter>
+    where
+        R: std::ops::RangeBounds<usize>,
+        I: IntoIterator<Item = T>,
+    {
+        Splice {
+            drain: self.drain(range),
+            replace_with: replace_with.into_iter(),
         }
     }
 
-    pub fn clear(&mut self) {
-        let mut remaining = self.len;
-        for block_idx in 0..BLOCK_COUNT {
-            if remaining == 0 {
-                break;
-            }
-            let len = self.block_lengths[block_idx];
-            let ptr = self.blocks[block_idx];
-            if len == 0 {
-                break;
+    /// Sorts self, keeping the order of equal elements. The elements are moved into a temporary
+    /// `Vec` for `slice::sort` and moved back block by block, which is faster than sorting across
+    /// the blocks but needs `len * size_of::<T>()` bytes of scratch memory. Use
+    /// `::sort_unstable` to sort without allocating
+    pub fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        self.with_contiguous(|elements| elements.sort());
+    }
+
+    /// Sorts self in place without allocating, equal elements may be reordered. This is a
+    /// heapsort over the segmented indexes, O(n log n) but with more index computations and
+    /// cache misses per comparison than sorting a contiguous slice
+    pub fn sort_unstable(&mut self)
+    where
+        T: Ord,
+    {
+        self.heapsort_by(T::cmp);
+    }
+
+    /// Sorts self with `compare`, keeping the order of equal elements. Uses scratch memory like
+    /// `::sort`
+    pub fn sort_by<F: FnMut(&T, &T) -> std::cmp::Ordering>(&mut self, compare: F) {
+        self.with_contiguous(|elements| elements.sort_by(compare));
+    }
+
+    /// Sorts self by the key `f` returns, keeping the order of equal elements. `f` is called
+    /// O(n log n) times, use `::sort_by_cached_key` for expensive keys
+    pub fn sort_by_key<K: Ord, F: FnMut(&T) -> K>(&mut self, f: F) {
+        self.with_contiguous(|elements| elements.sort_by_key(f));
+    }
+
+    /// Sorts self by the key `f` returns, keeping the order of equal elements. `f` is called
+    /// exactly once per element, the keys are kept in a scratch buffer next to the one `::sort`
+    /// uses
+    pub fn sort_by_cached_key<K: Ord, F: FnMut(&T) -> K>(&mut self, f: F) {
+        self.with_contiguous(|elements| elements.sort_by_cached_key(f));
+    }
+
+    /// Sorts self with `compare` in place without allocating, equal elements may be reordered.
+    /// See `::sort_unstable`
+    pub fn sort_unstable_by<F: FnMut(&T, &T) -> std::cmp::Ordering>(&mut self, compare: F) {
+        self.heapsort_by(compare);
+    }
+
+    /// Sorts self by the key `f` returns in place without allocating, equal elements may be
+    /// reordered. See `::sort_unstable`
+    pub fn sort_unstable_by_key<K: Ord, F: FnMut(&T) -> K>(&mut self, mut f: F) {
+        self.heapsort_by(|a, b| f(a).cmp(&f(b)));
+    }
+
+    /// Checks if the elements of self are sorted in ascending order, true for empty and single
+    /// element lists
+    pub fn is_sorted(&self) -> bool
+    where
+        T: PartialOrd,
+    {
+        self.is_sorted_by(|a, b| a <= b)
+    }
+
+    /// Checks if `compare` holds for every pair of adjacent elements, including the pairs
+    /// spanning two blocks
+    pub fn is_sorted_by<F: FnMut(&T, &T) -> bool>(&self, mut compare: F) -> bool {
+        let mut prev: Option<&T> = None;
+        for elements in self.segments() {
+            // the pair spanning the previous block and this one
+            if let (Some(prev), Some(first)) = (prev, elements.first()) {
+                if !compare(prev, first) {
+                    return false;
+                }
             }
-            let take = remaining.min(len);
-            for i in 0..take {
-                unsafe { (*ptr.add(i)).assume_init_drop() };
+            if !elements.is_sorted_by(&mut compare) {
+                return false;
             }
-            remaining -= take;
+            prev = elements.last();
         }
-        self.len = 0;
+        true
     }
-}
 
-impl<T> std::default::Default for SegmentedList<T> {
-    fn default() -> Self {
-        Self::new()
+    /// Resizes self to `new_len`, either truncating or pushing clones of `value` until the length
+    /// is reached
+    pub fn resize(&mut self, new_len: usize, value: T)
+    where
+        T: Clone,
+    {
+        if new_len <= self.len {
+            self.truncate(new_len);
+            return;
+        }
+
+        for _ in self.len..new_len - 1 {
+            self.push(value.clone());
+        }
+        // the last slot gets value itself, saving a clone
+        self.push(value);
     }
-}
 
-impl<T> std::ops::Index<usize> for SegmentedList<T> {
-    type Output = T;
+    /// Resizes self to `new_len`, either truncating or pushing the values returned by `f` until
+    /// the length is reached
+    pub fn resize_with<F: FnMut() -> T>(&mut self, new_len: usize, mut f: F) {
+        if new_len <= self.len {
+            self.truncate(new_len);
+            return;
+        }
 
-    fn index(&self, idx: usize) -> &Self::Output {
+        for _ in self.len..new_len {
+            self.push(f());
+        }
+    }
+
+    /// Overwrites every element with a clone of `value`, dropping the previous ones. The length
+    /// doesn't change
+    pub fn fill(&mut self, value: T)
+    where
+        T: Clone,
+    {
+        for v in self.iter_mut() {
+            *v = value.clone();
+        }
+    }
+
+    /// Overwrites every element with the value returned by `f`, dropping the previous ones. The
+    /// length doesn't change
+    pub fn fill_with<F: FnMut() -> T>(&mut self, mut f: F) {
+        for v in self.iter_mut() {
+            *v = f();
+        }
+    }
+
+    /// Removes and returns the element at `idx`, shifting all elements after it one slot to the
+    /// left. This is O(n), see `::insert`. Panics if `idx >= len`
+    pub fn remove(&mut self, idx: usize) -> T {
         if idx >= self.len {
             panic!(
                 "index {} out of bounds for List of length {}",
@@ -257,315 +786,4146 @@ impl<T> std::ops::Index<usize> for SegmentedList<T> {
             );
         }
 
-        let SegmentedIdx(block, block_index) = self.idx_to_block_idx(idx);
-        unsafe { (*self.blocks[block].add(block_index)).assume_init_ref() }
+        unsafe {
+            let v = (*self.slot(idx)).assume_init_read();
+            self.shift(idx + 1, idx, self.len - idx - 1);
+            self.set_len(self.len - 1);
+            v
+        }
     }
-}
 
-impl<T> std::ops::IndexMut<usize> for SegmentedList<T> {
-    fn index_mut(&mut self, idx: usize) -> &mut T {
+    /// Removes and returns the element at `idx` in O(1) by moving the last element into its
+    /// slot, thus doesn't preserve ordering. Panics if `idx >= len`
+    pub fn swap_remove(&mut self, idx: usize) -> T {
         if idx >= self.len {
             panic!(
-                "index {} out of bounds for List of length {}",
+                "swap_remove index (is {}) should be < len (is {})",
                 idx, self.len
             );
         }
 
-        let SegmentedIdx(block, block_index) = self.idx_to_block_idx(idx);
-        unsafe { (*self.blocks[block].add(block_index)).assume_init_mut() }
+        let last = self.len - 1;
+        unsafe {
+            let v = (*self.slot(idx)).assume_init_read();
+            if idx != last {
+                std::ptr::copy_nonoverlapping(self.slot(last), self.slot(idx), 1);
+            }
+            self.set_len(last);
+            v
+        }
     }
-}
 
-impl<T: Clone + Copy> Clone for SegmentedList<T> {
-    fn clone(&self) -> Self {
-        let mut new_list = SegmentedList::new();
-        new_list.len = self.len;
+    /// Keeps only the elements for which `f` returns true, dropping the rest and compacting the
+    /// survivors towards the front in their original order. Blocks aren't released, capacity
+    /// stays the same.
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F) {
+        self.retain_mut(|v| f(v))
+    }
 
-        for block_idx in 0..BLOCK_COUNT {
-            if self.block_lengths[block_idx] == 0 {
-                break;
-            }
-            let src_ptr = self.blocks[block_idx];
-            let elems = self.block_lengths[block_idx];
-            if elems == 0 {
-                continue;
-            }
-            new_list.alloc_block(block_idx);
-            let dst_ptr = new_list.blocks[block_idx];
+    /// Same as `::retain`, but `f` may mutate the elements it is passed
+    pub fn retain_mut<F: FnMut(&mut T) -> bool>(&mut self, mut f: F) {
+        /// Closes the gap left by deleted elements and fixes the length, also if `f` or a
+        /// destructor panics half way through
+        struct BackshiftOnDrop<'a, T> {
+            list: &'a mut SegmentedList<T>,
+            processed: usize,
+            deleted: usize,
+            original_len: usize,
+        }
 
-            for i in 0..elems {
-                unsafe {
-                    let val = (*src_ptr.add(i)).assume_init();
-                    (*dst_ptr.add(i)).write(val);
+        impl<T> Drop for BackshiftOnDrop<'_, T> {
+            fn drop(&mut self) {
+                if self.deleted > 0 {
+                    unsafe {
+                        self.list.shift(
+                            self.processed,
+                            self.processed - self.deleted,
+                            self.original_len - self.processed,
+                        )
+                    };
+                }
+                self.list.set_len(self.original_len - self.deleted);
+            }
+        }
+
+        let original_len = self.len;
+        // the list doesn't own its elements while they are being moved around
+        self.set_len(0);
+        let mut g = BackshiftOnDrop {
+            list: self,
+            processed: 0,
+            deleted: 0,
+            original_len,
+        };
+
+        while g.processed < original_len {
+            let cur = g.list.slot(g.processed);
+            if !f(unsafe { (*cur).assume_init_mut() }) {
+                g.processed += 1;
+                g.deleted += 1;
+                unsafe { (*cur).assume_init_drop() };
+                continue;
+            }
+            if g.deleted > 0 {
+                let hole = g.list.slot(g.processed - g.deleted);
+                unsafe { std::ptr::copy_nonoverlapping(cur, hole, 1) };
+            }
+            g.processed += 1;
+        }
+    }
+
+    /// Swaps the elements at `a` and `b` in place, panics if either is out of bounds
+    pub fn swap(&mut self, a: usize, b: usize) {
+        for idx in [a, b] {
+            if idx >= self.len {
+                panic!(
+                    "index {} out of bounds for List of length {}",
+                    idx, self.len
+                );
+            }
+        }
+
+        if a == b {
+            return;
+        }
+
+        // raw pointers instead of two &mut T, the slots may live in different blocks
+        unsafe { std::ptr::swap(self.slot(a), self.slot(b)) }
+    }
+
+    /// Reverses the order of the elements in place, swapping pairs from both ends towards the
+    /// middle
+    pub fn reverse(&mut self) {
+        // both ends of the iterator never yield the same element, so the pairs never alias
+        let mut elements = self.iter_mut();
+        while let (Some(front), Some(back)) = (elements.next(), elements.next_back()) {
+            std::mem::swap(front, back);
+        }
+    }
+
+    /// Returns a view of the elements in `range`, the range form of indexing. `Index` has to
+    /// return a reference, which a view spanning several blocks can't be, so `list[2..5]` is
+    /// spelled `list.slice(2..5)`. Panics if the range is out of bounds
+    pub fn slice<R: std::ops::RangeBounds<usize>>(&self, range: R) -> SegmentedSlice<'_, T> {
+        let std::ops::Range { start, end } = to_range(range, self.len);
+        SegmentedSlice {
+            list: self,
+            start,
+            len: end - start,
+        }
+    }
+
+    /// Returns an iterator over all windows of `size` consecutive elements, advancing by one
+    /// element. Windows spanning a block boundary can't be a `&[T]`, thus they are yielded as
+    /// `SegmentedSlice`. Yields nothing if self is shorter than `size`, panics if `size` is 0
+    pub fn windows(&self, size: usize) -> Windows<'_, T> {
+        assert!(size != 0, "window size must be non-zero");
+        Windows {
+            list: self,
+            size,
+            next: 0,
+            end: (self.len + 1).saturating_sub(size),
+        }
+    }
+
+    /// Returns an iterator over consecutive chunks of `size` elements, the last chunk is shorter
+    /// if `size` doesn't divide the length. Chunks are yielded as `SegmentedSlice` like
+    /// `::windows`. Panics if `size` is 0
+    pub fn chunks(&self, size: usize) -> Chunks<'_, T> {
+        assert!(size != 0, "chunk size must be non-zero");
+        Chunks {
+            list: self,
+            size,
+            next: 0,
+            end: self.len,
+        }
+    }
+
+    /// Returns an iterator over references to the elements of self
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            list: self,
+            cursor: Cursor::new(self, 0..self.len),
+        }
+    }
+
+    /// Returns an iterator over mutable references to the elements of self, each element is
+    /// yielded exactly once, so all references handed out may be held at the same time
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            cursor: Cursor::new(self, 0..self.len),
+            list: self,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Returns the length of self
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Collects self and its contents into a vec
+    pub fn to_vec(mut self) -> Vec<T> {
+        let mut result = Vec::with_capacity(self.len);
+        let mut remaining = self.len;
+        // the elements are moved into result, thus Drop must not drop them again
+        self.len = 0;
+
+        for block_idx in 0..BLOCK_COUNT {
+            if remaining == 0 {
+                break;
+            }
+
+            let len = self.block_lengths[block_idx];
+            if len == 0 {
+                break;
+            }
+
+            let ptr = self.blocks[block_idx];
+            let take = remaining.min(len);
+            for i in 0..take {
+                let value = unsafe { (*ptr.add(i)).assume_init_read() };
+                result.push(value);
+            }
+            remaining -= take;
+            // We "forget" the block, no dealloc, bump allocator manages memory
+            self.blocks[block_idx] = std::ptr::null_mut();
+        }
+        result
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.block_lengths.iter().copied().sum()
+    }
+
+    /// Allocates the missing blocks needed to hold `len + additional` elements, existing elements
+    /// are never moved. Does nothing if the capacity already suffices
+    pub fn reserve(&mut self, additional: usize) {
+        let cap = self.len.checked_add(additional).expect("capacity overflow");
+        self.grow_to(cap);
+    }
+
+    /// Same as `::reserve`, exists for `std::vec::Vec` parity. Since memory is only ever
+    /// allocated in whole blocks, the capacity may still end up larger than `len + additional`
+    pub fn reserve_exact(&mut self, additional: usize) {
+        self.reserve(additional);
+    }
+
+    /// Returns the number of blocks currently backed by memory
+    pub fn allocated_blocks(&self) -> usize {
+        self.block_lengths.iter().filter(|&&len| len != 0).count()
+    }
+
+    /// Releases the blocks holding no elements, the first block is always kept. The blocks are
+    /// the last allocations of the list's allocator, so it is rewound to the first released one
+    /// and unmaps the regions behind it, memory sharing a region with kept blocks is only reused
+    pub fn shrink_to_fit(&mut self) {
+        let keep = (0..BLOCK_COUNT)
+            .take_while(|&block| BLOCK_STARTS[block] < self.len)
+            .count()
+            .max(1);
+        if keep == BLOCK_COUNT || self.block_lengths[keep] == 0 {
+            return;
+        }
+
+        // blocks are allocated in order, thus everything requested from block keep on is unused
+        let first_released = std::ptr::NonNull::new(self.blocks[keep] as *mut u8)
+            .expect("allocated block is never null");
+        self.allocator.release_from(first_released);
+        for block in keep..BLOCK_COUNT {
+            self.blocks[block] = std::ptr::null_mut();
+            self.block_lengths[block] = 0;
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if self holds an element equal to `value`, stops at the first match
+    pub fn contains(&self, value: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.segments().any(|elements| elements.contains(value))
+    }
+
+    /// Returns the index of the first element matching `pred`, scanning the blocks front to back
+    pub fn position<P: FnMut(&T) -> bool>(&self, mut pred: P) -> Option<usize> {
+        self.segments()
+            .zip(BLOCK_STARTS)
+            .find_map(|(elements, start)| elements.iter().position(&mut pred).map(|i| start + i))
+    }
+
+    /// Returns the index of the last element matching `pred`, scanning the blocks back to front
+    pub fn rposition<P: FnMut(&T) -> bool>(&self, mut pred: P) -> Option<usize> {
+        let used_blocks = self.segments().count();
+        (0..used_blocks).rev().find_map(|block| {
+            self.block_slice(block)
+                .iter()
+                .rposition(&mut pred)
+                .map(|i| BLOCK_STARTS[block] + i)
+        })
+    }
+
+    /// Binary searches a sorted self for `x`, indexing straight into the blocks. Returns
+    /// `Ok(idx)` of a matching element, if there are several any of them may be returned, or
+    /// `Err(idx)` of where `x` could be inserted while keeping self sorted
+    pub fn binary_search(&self, x: &T) -> Result<usize, usize>
+    where
+        T: Ord,
+    {
+        self.binary_search_by(|v| v.cmp(x))
+    }
+
+    /// Binary searches a self sorted by `compare`, which returns how an element orders relative
+    /// to the target. Each probe is a single segmented index computation. See `::binary_search`
+    pub fn binary_search_by<F: FnMut(&T) -> std::cmp::Ordering>(
+        &self,
+        mut compare: F,
+    ) -> Result<usize, usize> {
+        let (mut low, mut high) = (0, self.len);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            // mid < len, so its slot is initialized
+            let v = unsafe { (*self.slot(mid)).assume_init_ref() };
+            match compare(v) {
+                std::cmp::Ordering::Less => low = mid + 1,
+                std::cmp::Ordering::Greater => high = mid,
+                std::cmp::Ordering::Equal => return Ok(mid),
+            }
+        }
+        Err(low)
+    }
+
+    /// Binary searches a self sorted by the key `f` returns for `key`. See `::binary_search`
+    pub fn binary_search_by_key<B: Ord, F: FnMut(&T) -> B>(
+        &self,
+        key: &B,
+        mut f: F,
+    ) -> Result<usize, usize> {
+        self.binary_search_by(|v| f(v).cmp(key))
+    }
+
+    /// Returns the index of the first element for which `pred` is false, assuming self is
+    /// partitioned so all elements matching `pred` come first. Returns `len` if `pred` holds for
+    /// every element. Probes like `::binary_search_by`
+    pub fn partition_point<P: FnMut(&T) -> bool>(&self, mut pred: P) -> usize {
+        let (mut low, mut high) = (0, self.len);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            // mid < len, so its slot is initialized
+            let v = unsafe { (*self.slot(mid)).assume_init_ref() };
+            if pred(v) {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        if self.len == 0 {
+            None
+        } else {
+            // first element is always at idx 0 of block 0, thus we hardcode this
+            unsafe { self.get_with_segmented_idx(SegmentedIdx(0, 0)) }
+        }
+    }
+
+    pub fn first_mut(&mut self) -> Option<&mut T> {
+        if self.len == 0 {
+            None
+        } else {
+            unsafe { self.get_mut_with_segmented_idx(SegmentedIdx(0, 0)) }
+        }
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        if self.len == 0 {
+            None
+        } else {
+            let si = self.idx_to_block_idx(self.len - 1);
+            unsafe { self.get_with_segmented_idx(si) }
+        }
+    }
+
+    pub fn last_mut(&mut self) -> Option<&mut T> {
+        if self.len == 0 {
+            None
+        } else {
+            let si = self.idx_to_block_idx(self.len - 1);
+            unsafe { self.get_mut_with_segmented_idx(si) }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Shortens self to `new_len` elements, dropping the rest. The blocks stay allocated and are
+    /// reused by subsequent pushes. No-op if `new_len >= len`
+    pub fn truncate(&mut self, new_len: usize) {
+        /// Drops the tail block by block, if a destructor panics the guard keeps dropping the
+        /// following blocks while unwinding
+        struct DropTail<'a, T> {
+            list: &'a SegmentedList<T>,
+            block: usize,
+            block_index: usize,
+            remaining: usize,
+        }
+
+        impl<T> DropTail<'_, T> {
+            fn drop_remaining(&mut self) {
+                while self.remaining > 0 {
+                    let take = self
+                        .remaining
+                        .min(self.list.block_lengths[self.block] - self.block_index);
+                    let start = unsafe { self.list.blocks[self.block].add(self.block_index) };
+                    // advance before dropping, a panicking destructor must not revisit this block
+                    self.remaining -= take;
+                    self.block += 1;
+                    self.block_index = 0;
+                    unsafe {
+                        std::ptr::drop_in_place(std::ptr::slice_from_raw_parts_mut(
+                            start as *mut T,
+                            take,
+                        ))
+                    };
+                }
+            }
+        }
+
+        impl<T> Drop for DropTail<'_, T> {
+            fn drop(&mut self) {
+                self.drop_remaining();
+            }
+        }
+
+        if new_len >= self.len {
+            return;
+        }
+
+        let remaining = self.len - new_len;
+        let SegmentedIdx(block, block_index) = self.idx_to_block_idx(new_len);
+        // shrink first, thus a panicking destructor can't lead to a double drop
+        self.set_len(new_len);
+        DropTail {
+            list: self,
+            block,
+            block_index,
+            remaining,
+        }
+        .drop_remaining();
+    }
+}
+
+/// Resolves `range` against `len`, panicking like slice indexing if it is out of bounds
+fn to_range<R: std::ops::RangeBounds<usize>>(range: R, len: usize) -> std::ops::Range<usize> {
+    use std::ops::Bound;
+
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start
+            .checked_add(1)
+            .expect("attempted to index slice from after maximum usize"),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end
+            .checked_add(1)
+            .expect("attempted to index slice up to maximum usize"),
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+    if start > end {
+        panic!("slice index starts at {} but ends at {}", start, end);
+    }
+    if end > len {
+        panic!(
+            "range end index {} out of range for slice of length {}",
+            end, len
+        );
+    }
+    start..end
+}
+
+impl<T> std::default::Default for SegmentedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Formats as `[elem0, elem1, ...]`, formatting options are applied to every element
+impl<T: std::fmt::Display> std::fmt::Display for SegmentedList<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("[")?;
+        for (i, v) in self.iter().enumerate() {
+            if i != 0 {
+                f.write_str(", ")?;
+            }
+            v.fmt(f)?;
+        }
+        f.write_str("]")
+    }
+}
+
+/// Formats like a slice, `{:#?}` additionally breaks the elements down into their blocks
+impl<T: std::fmt::Debug> std::fmt::Debug for SegmentedList<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        struct Block<'a, T> {
+            index: usize,
+            capacity: usize,
+            elements: &'a [T],
+        }
+
+        impl<T: std::fmt::Debug> std::fmt::Debug for Block<'_, T> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct("Block")
+                    .field("index", &self.index)
+                    .field("capacity", &self.capacity)
+                    .field("used", &self.elements.len())
+                    .field("elements", &self.elements)
+                    .finish()
+            }
+        }
+
+        struct Blocks<'a, T>(&'a SegmentedList<T>);
+
+        impl<T: std::fmt::Debug> std::fmt::Debug for Blocks<'_, T> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let list = self.0;
+                f.debug_list()
+                    .entries(
+                        (0..BLOCK_COUNT)
+                            .take_while(|&block| list.block_lengths[block] != 0)
+                            .map(|block| Block {
+                                index: block,
+                                capacity: list.block_lengths[block],
+                                elements: list.block_slice(block),
+                            }),
+                    )
+                    .finish()
+            }
+        }
+
+        if !f.alternate() {
+            return f.debug_list().entries(self.iter()).finish();
+        }
+
+        f.debug_struct("SegmentedList")
+            .field("len", &self.len)
+            .field("blocks", &Blocks(self))
+            .finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for SegmentedList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        // block sizes only depend on the block index, thus lists of equal length fill their
+        // blocks identically and can be compared slice by slice
+        self.len == other.len && self.segments().zip(other.segments()).all(|(a, b)| a == b)
+    }
+}
+
+impl<T: Eq> Eq for SegmentedList<T> {}
+
+impl<T> SegmentedList<T> {
+    /// Compares self block by block against the matching ranges of a contiguous slice
+    fn eq_slice<U>(&self, other: &[U]) -> bool
+    where
+        T: PartialEq<U>,
+    {
+        self.len == other.len()
+            && self
+                .segments()
+                .zip(BLOCK_STARTS)
+                .all(|(elements, start)| elements == &other[start..start + elements.len()])
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<Vec<U>> for SegmentedList<T> {
+    fn eq(&self, other: &Vec<U>) -> bool {
+        self.eq_slice(other)
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<[U]> for SegmentedList<T> {
+    fn eq(&self, other: &[U]) -> bool {
+        self.eq_slice(other)
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<&[U]> for SegmentedList<T> {
+    fn eq(&self, other: &&[U]) -> bool {
+        self.eq_slice(other)
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize> PartialEq<[U; N]> for SegmentedList<T> {
+    fn eq(&self, other: &[U; N]) -> bool {
+        self.eq_slice(other)
+    }
+}
+
+/// Compares the ranges of a contiguous slice block by block against `list`, the mirror of
+/// `SegmentedList::eq_slice`. T == U doesn't imply U == T, thus the operands can't be flipped.
+fn slice_eq_list<T: PartialEq<U>, U>(slice: &[T], list: &SegmentedList<U>) -> bool {
+    slice.len() == list.len
+        && list
+            .segments()
+            .zip(BLOCK_STARTS)
+            .all(|(elements, start)| &slice[start..start + elements.len()] == elements)
+}
+
+impl<T: PartialEq<U>, U> PartialEq<SegmentedList<U>> for Vec<T> {
+    fn eq(&self, other: &SegmentedList<U>) -> bool {
+        slice_eq_list(self, other)
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<SegmentedList<U>> for [T] {
+    fn eq(&self, other: &SegmentedList<U>) -> bool {
+        slice_eq_list(self, other)
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<SegmentedList<U>> for &[T] {
+    fn eq(&self, other: &SegmentedList<U>) -> bool {
+        slice_eq_list(self, other)
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize> PartialEq<SegmentedList<U>> for [T; N] {
+    fn eq(&self, other: &SegmentedList<U>) -> bool {
+        slice_eq_list(self, other)
+    }
+}
+
+/// Lexicographic ordering like `[T]`, the blocks of two lists line up, thus only the block a list
+/// ends in can differ in length and the slice comparison covers the prefix case
+impl<T: PartialOrd> PartialOrd for SegmentedList<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        for (a, b) in self.segments().zip(other.segments()) {
+            match a.partial_cmp(b) {
+                Some(std::cmp::Ordering::Equal) => {}
+                non_eq => return non_eq,
+            }
+        }
+        self.len.partial_cmp(&other.len)
+    }
+}
+
+impl<T: Ord> Ord for SegmentedList<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        for (a, b) in self.segments().zip(other.segments()) {
+            match a.cmp(b) {
+                std::cmp::Ordering::Equal => {}
+                non_eq => return non_eq,
+            }
+        }
+        self.len.cmp(&other.len)
+    }
+}
+
+/// Hashes like `[T]`: the length followed by the elements. Since the elements are fed block by
+/// block, the hash equals the one of a `Vec` holding the same elements for every hasher whose
+/// output doesn't depend on how the input bytes are split across `write` calls, such as the ones
+/// in std
+impl<T: std::hash::Hash> std::hash::Hash for SegmentedList<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len);
+        for elements in self.segments() {
+            T::hash_slice(elements, state);
+        }
+    }
+}
+
+impl<T> std::ops::Index<usize> for SegmentedList<T> {
+    type Output = T;
+
+    fn index(&self, idx: usize) -> &Self::Output {
+        if idx >= self.len {
+            panic!(
+                "index {} out of bounds for List of length {}",
+                idx, self.len
+            );
+        }
+
+        let SegmentedIdx(block, block_index) = self.idx_to_block_idx(idx);
+        unsafe { (*self.blocks[block].add(block_index)).assume_init_ref() }
+    }
+}
+
+impl<T> std::ops::IndexMut<usize> for SegmentedList<T> {
+    fn index_mut(&mut self, idx: usize) -> &mut T {
+        if idx >= self.len {
+            panic!(
+                "index {} out of bounds for List of length {}",
+                idx, self.len
+            );
+        }
+
+        let SegmentedIdx(block, block_index) = self.idx_to_block_idx(idx);
+        unsafe { (*self.blocks[block].add(block_index)).assume_init_mut() }
+    }
+}
+
+impl<T: Clone> Clone for SegmentedList<T> {
+    fn clone(&self) -> Self {
+        let mut new_list = SegmentedList::new();
+        // pushing keeps new_list.len in sync with its initialized elements, thus if T::clone
+        // panics, dropping new_list only drops the elements cloned so far
+        for v in self.iter() {
+            new_list.push(v.clone());
+        }
+        new_list
+    }
+}
+
+impl<T> Extend<T> for SegmentedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<T> std::iter::FromIterator<T> for SegmentedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut sl = SegmentedList::new();
+        sl.extend(iter);
+        sl
+    }
+}
+
+impl<T> From<Vec<T>> for SegmentedList<T> {
+    /// Moves the elements of `vec` into a new list, copying them block by block
+    fn from(mut vec: Vec<T>) -> Self {
+        let mut list = SegmentedList::new();
+        let len = vec.len();
+        unsafe {
+            // vec only frees its buffer from here on, the elements now belong to list
+            vec.set_len(0);
+            list.append_raw(vec.as_ptr(), len);
+        }
+        list
+    }
+}
+
+impl<T: Clone> From<&[T]> for SegmentedList<T> {
+    /// Clones the elements of `slice` into a new list, allocating all needed blocks up front
+    fn from(slice: &[T]) -> Self {
+        let mut list = SegmentedList::new();
+        list.grow_to(slice.len());
+        for v in slice {
+            list.push(v.clone());
+        }
+        list
+    }
+}
+
+impl<T> From<SegmentedList<T>> for Vec<T> {
+    /// Moves the elements of `list` into a `Vec`, same as `SegmentedList::to_vec`
+    fn from(list: SegmentedList<T>) -> Self {
+        list.to_vec()
+    }
+}
+
+impl SegmentedList<u8> {
+    /// Returns a reader over all bytes of self, same as `list.slice(..)`. The view implements
+    /// `std::io::Read` and borrows the list, so it can't change underneath the reader
+    pub fn reader(&self) -> SegmentedSlice<'_, u8> {
+        self.slice(..)
+    }
+}
+
+/// Appends written bytes to the end of the list, so it can be used as a growable byte sink
+impl std::io::Write for SegmentedList<u8> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.push_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Reads the bytes from the front of the view and advances past them, like `&[u8]`. A
+/// `SegmentedList<u8>` is read via `SegmentedList::reader`
+impl std::io::Read for SegmentedSlice<'_, u8> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let mut read = 0;
+        while read < buf.len() && self.len > 0 {
+            let SegmentedIdx(block, block_index) = self.list.idx_to_block_idx(self.start);
+            let available = &self.list.block_slice(block)[block_index..];
+            let n = available.len().min(self.len).min(buf.len() - read);
+            buf[read..read + n].copy_from_slice(&available[..n]);
+            read += n;
+            self.start += n;
+            self.len -= n;
+        }
+        Ok(read)
+    }
+}
+
+/// Serializes as a sequence, element by element straight out of the blocks
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for SegmentedList<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeSeq;
+
+        let mut seq = serializer.serialize_seq(Some(self.len))?;
+        for block in self.segments() {
+            for v in block {
+                seq.serialize_element(v)?;
+            }
+        }
+        seq.end()
+    }
+}
+
+/// Deserializes from a sequence, pushing elements as they arrive. Blocks for the sequence's size
+/// hint are allocated up front, capped at 1MiB so a hostile hint can't reserve arbitrary memory
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for SegmentedList<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ListVisitor<T>(std::marker::PhantomData<T>);
+
+        impl<'de, T: serde::Deserialize<'de>> serde::de::Visitor<'de> for ListVisitor<T> {
+            type Value = SegmentedList<T>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a sequence")
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<Self::Value, A::Error> {
+                const MAX_PREALLOC_BYTES: usize = 1024 * 1024;
+
+                let hint = seq
+                    .size_hint()
+                    .unwrap_or(0)
+                    .min(MAX_PREALLOC_BYTES / size_of::<T>().max(1));
+                let mut list = SegmentedList::with_capacity(hint);
+                while let Some(v) = seq.next_element()? {
+                    list.push(v);
+                }
+                Ok(list)
+            }
+        }
+
+        deserializer.deserialize_seq(ListVisitor(std::marker::PhantomData))
+    }
+}
+
+/// Cursor walks the initialized prefix of a `SegmentedList` block by block and is shared by all
+/// of its iterators. It holds a pointer into the current block from both ends and only consults
+/// the block tables once that block is exhausted, so advancing is a pointer bump in the common
+/// case. Both ends share `remaining`, once it hits zero they have met and the cursor is exhausted.
+struct Cursor<T> {
+    block: usize,
+    ptr: *mut MaybeUninit<T>,
+    end: *mut MaybeUninit<T>,
+    back_block: usize,
+    /// start of the block `back_ptr` points into
+    back_start: *mut MaybeUninit<T>,
+    /// one past the last slot not yet yielded from the back
+    back_ptr: *mut MaybeUninit<T>,
+    remaining: usize,
+}
+
+impl<T> Cursor<T> {
+    /// Creates a cursor over the initialized elements in `range`
+    fn new(list: &SegmentedList<T>, range: std::ops::Range<usize>) -> Self {
+        if range.is_empty() {
+            let ptr = list.blocks[0];
+            return Self {
+                block: 0,
+                ptr,
+                end: ptr,
+                back_block: 0,
+                back_start: ptr,
+                back_ptr: ptr,
+                remaining: 0,
+            };
+        }
+
+        let SegmentedIdx(block, block_index) = list.idx_to_block_idx(range.start);
+        let start = list.blocks[block];
+        let (ptr, end) = unsafe { (start.add(block_index), start.add(list.block_lengths[block])) };
+
+        let SegmentedIdx(back_block, block_index) = list.idx_to_block_idx(range.end - 1);
+        let back_start = list.blocks[back_block];
+
+        Self {
+            block,
+            ptr,
+            end,
+            back_block,
+            back_start,
+            back_ptr: unsafe { back_start.add(block_index + 1) },
+            remaining: range.len(),
+        }
+    }
+
+    #[inline(always)]
+    fn next(&mut self, list: &SegmentedList<T>) -> Option<*mut MaybeUninit<T>> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        if self.ptr == self.end {
+            self.block += 1;
+            self.ptr = list.blocks[self.block];
+            self.end = unsafe { self.ptr.add(list.block_lengths[self.block]) };
+        }
+
+        let slot = self.ptr;
+        self.ptr = unsafe { self.ptr.add(1) };
+        self.remaining -= 1;
+        Some(slot)
+    }
+
+    #[inline(always)]
+    fn next_back(&mut self, list: &SegmentedList<T>) -> Option<*mut MaybeUninit<T>> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        if self.back_ptr == self.back_start {
+            self.back_block -= 1;
+            self.back_start = list.blocks[self.back_block];
+            self.back_ptr = unsafe { self.back_start.add(list.block_lengths[self.back_block]) };
+        }
+
+        self.back_ptr = unsafe { self.back_ptr.sub(1) };
+        self.remaining -= 1;
+        Some(self.back_ptr)
+    }
+}
+
+/// Iter is the borrowing iterator over a `SegmentedList`, created by `SegmentedList::iter`.
+pub struct Iter<'a, T> {
+    list: &'a SegmentedList<T>,
+    cursor: Cursor<T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.cursor
+            .next(self.list)
+            .map(|slot| unsafe { (*slot).assume_init_ref() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.cursor.remaining, Some(self.cursor.remaining))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.cursor
+            .next_back(self.list)
+            .map(|slot| unsafe { (*slot).assume_init_ref() })
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> std::iter::FusedIterator for Iter<'_, T> {}
+
+/// IterMut is the mutable borrowing iterator over a `SegmentedList`, created by
+/// `SegmentedList::iter_mut`.
+pub struct IterMut<'a, T> {
+    // shared borrow of the list itself, the elements live in the blocks and are only ever handed
+    // out once, thus the mutable references never alias
+    list: &'a SegmentedList<T>,
+    cursor: Cursor<T>,
+    _marker: std::marker::PhantomData<&'a mut T>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.cursor
+            .next(self.list)
+            .map(|slot| unsafe { (*slot).assume_init_mut() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.cursor.remaining, Some(self.cursor.remaining))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.cursor
+            .next_back(self.list)
+            .map(|slot| unsafe { (*slot).assume_init_mut() })
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<T> std::iter::FusedIterator for IterMut<'_, T> {}
+
+/// IntoIter is the consuming iterator over a `SegmentedList`, created by
+/// `SegmentedList::into_iter`, moving each element out of its block.
+pub struct IntoIter<T> {
+    /// keeps the blocks alive, its len is zeroed on creation since the iterator owns the elements
+    list: SegmentedList<T>,
+    cursor: Cursor<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.cursor
+            .next(&self.list)
+            .map(|slot| unsafe { (*slot).assume_init_read() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.cursor.remaining, Some(self.cursor.remaining))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.cursor
+            .next_back(&self.list)
+            .map(|slot| unsafe { (*slot).assume_init_read() })
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> std::iter::FusedIterator for IntoIter<T> {}
+
+impl<T> Drop for IntoIter<T> {
+    fn drop(&mut self) {
+        // drop the elements not yet yielded, the list then releases the arena on its own
+        while let Some(slot) = self.cursor.next(&self.list) {
+            unsafe { (*slot).assume_init_drop() };
+        }
+    }
+}
+
+/// Draining iterator for `SegmentedList`, created by `SegmentedList::drain`
+pub struct Drain<'a, T> {
+    /// len is set to the start of the drained range while the iterator lives
+    list: &'a mut SegmentedList<T>,
+    /// idx of the next element yielded from the front
+    next: usize,
+    /// idx after the next element yielded from the back
+    end: usize,
+    /// idx of the first element behind the drained range
+    tail_start: usize,
+    tail_len: usize,
+}
+
+impl<T> Iterator for Drain<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next == self.end {
+            return None;
+        }
+        self.next += 1;
+        Some(unsafe { (*self.list.slot(self.next - 1)).assume_init_read() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.next;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T> DoubleEndedIterator for Drain<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.next == self.end {
+            return None;
+        }
+        self.end -= 1;
+        Some(unsafe { (*self.list.slot(self.end)).assume_init_read() })
+    }
+}
+
+impl<T> ExactSizeIterator for Drain<'_, T> {}
+
+impl<T> std::iter::FusedIterator for Drain<'_, T> {}
+
+impl<T> Drop for Drain<'_, T> {
+    fn drop(&mut self) {
+        /// Shifts the tail into the gap, also runs if dropping an unconsumed element panics, the
+        /// remaining unconsumed elements are leaked in that case
+        struct MoveTail<'r, 'a, T>(&'r mut Drain<'a, T>);
+
+        impl<T> Drop for MoveTail<'_, '_, T> {
+            fn drop(&mut self) {
+                let drain = &mut *self.0;
+                let start = drain.list.len;
+                if drain.tail_start != start {
+                    unsafe { drain.list.shift(drain.tail_start, start, drain.tail_len) };
+                }
+                drain.list.set_len(start + drain.tail_len);
+            }
+        }
+
+        let guard = MoveTail(self);
+        while guard.0.next < guard.0.end {
+            guard.0.next += 1;
+            unsafe { (*guard.0.list.slot(guard.0.next - 1)).assume_init_drop() };
+        }
+    }
+}
+
+/// Splicing iterator for `SegmentedList`, created by `SegmentedList::splice`
+pub struct Splice<'a, I: Iterator + 'a> {
+    drain: Drain<'a, I::Item>,
+    replace_with: I,
+}
+
+impl<I: Iterator> Iterator for Splice<'_, I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.drain.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.drain.size_hint()
+    }
+}
+
+impl<I: Iterator> DoubleEndedIterator for Splice<'_, I> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.drain.next_back()
+    }
+}
+
+impl<I: Iterator> ExactSizeIterator for Splice<'_, I> {}
+
+impl<I: Iterator> Drop for Splice<'_, I> {
+    fn drop(&mut self) {
+        self.drain.by_ref().for_each(drop);
+
+        // the gap between the list and the tail is allocated, thus it is filled in place. Every
+        // written element bumps len, so the drain moves the tail behind it even if next() panics
+        let drain = &mut self.drain;
+        while drain.list.len < drain.tail_start {
+            let Some(v) = self.replace_with.next() else {
+                return;
+            };
+            let len = drain.list.len;
+            unsafe { (*drain.list.slot(len)).write(v) };
+            drain.list.set_len(len + 1);
+        }
+
+        let mut rest: Vec<I::Item> = self.replace_with.by_ref().collect();
+        let count = rest.len();
+        if count == 0 {
+            return;
+        }
+        let start = drain.list.len;
+        drain.list.grow_to(start + count + drain.tail_len);
+        unsafe {
+            drain
+                .list
+                .shift(drain.tail_start, start + count, drain.tail_len);
+            rest.set_len(0);
+            drain.list.append_raw(rest.as_ptr(), count);
+        }
+        // the tail already sits behind the new len, Drain's drop only restores the length
+        drain.tail_start = start + count;
+    }
+}
+
+impl<T> IntoIterator for SegmentedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(mut self) -> Self::IntoIter {
+        let cursor = Cursor::new(&self, 0..self.len);
+        // the iterator takes ownership of the elements, the list only keeps the blocks alive
+        self.len = 0;
+        IntoIter { list: self, cursor }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SegmentedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut SegmentedList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// SegmentedSlice is a borrowed view of a range of a `SegmentedList`, created by
+/// `SegmentedList::slice`. Since the blocks aren't contiguous it can't be a `&[T]`, it instead
+/// stores the range and resolves indexes relative to its start.
+pub struct SegmentedSlice<'a, T> {
+    list: &'a SegmentedList<T>,
+    start: usize,
+    len: usize,
+}
+
+impl<T> Clone for SegmentedSlice<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for SegmentedSlice<'_, T> {}
+
+impl<'a, T> SegmentedSlice<'a, T> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&'a T> {
+        if idx >= self.len {
+            return None;
+        }
+        self.list.get(self.start + idx)
+    }
+
+    pub fn first(&self) -> Option<&'a T> {
+        self.get(0)
+    }
+
+    pub fn last(&self) -> Option<&'a T> {
+        self.get(self.len.checked_sub(1)?)
+    }
+
+    /// Returns a view of `range` relative to the start of self. Panics if the range is out of
+    /// bounds
+    pub fn slice<R: std::ops::RangeBounds<usize>>(&self, range: R) -> SegmentedSlice<'a, T> {
+        let std::ops::Range { start, end } = to_range(range, self.len);
+        SegmentedSlice {
+            list: self.list,
+            start: self.start + start,
+            len: end - start,
+        }
+    }
+
+    /// Returns an iterator over references to the elements of self
+    pub fn iter(&self) -> Iter<'a, T> {
+        Iter {
+            list: self.list,
+            cursor: Cursor::new(self.list, self.start..self.start + self.len),
+        }
+    }
+}
+
+impl<T> std::ops::Index<usize> for SegmentedSlice<'_, T> {
+    type Output = T;
+
+    fn index(&self, idx: usize) -> &Self::Output {
+        match self.get(idx) {
+            Some(v) => v,
+            None => panic!(
+                "index {} out of bounds for SegmentedSlice of length {}",
+                idx, self.len
+            ),
+        }
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for SegmentedSlice<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T> IntoIterator for SegmentedSlice<'a, T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &SegmentedSlice<'a, T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over overlapping windows of a `SegmentedList`, created by `SegmentedList::windows`
+pub struct Windows<'a, T> {
+    list: &'a SegmentedList<T>,
+    size: usize,
+    /// start of the next window yielded from the front
+    next: usize,
+    /// start after the next window yielded from the back
+    end: usize,
+}
+
+impl<'a, T> Iterator for Windows<'a, T> {
+    type Item = SegmentedSlice<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next == self.end {
+            return None;
+        }
+        self.next += 1;
+        Some(SegmentedSlice {
+            list: self.list,
+            start: self.next - 1,
+            len: self.size,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.next;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T> DoubleEndedIterator for Windows<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.next == self.end {
+            return None;
+        }
+        self.end -= 1;
+        Some(SegmentedSlice {
+            list: self.list,
+            start: self.end,
+            len: self.size,
+        })
+    }
+}
+
+impl<T> ExactSizeIterator for Windows<'_, T> {}
+
+impl<T> std::iter::FusedIterator for Windows<'_, T> {}
+
+/// Iterator over non-overlapping chunks of a `SegmentedList`, created by `SegmentedList::chunks`
+pub struct Chunks<'a, T> {
+    list: &'a SegmentedList<T>,
+    size: usize,
+    /// start of the next chunk yielded from the front
+    next: usize,
+    /// end of the next chunk yielded from the back
+    end: usize,
+}
+
+impl<'a, T> Iterator for Chunks<'a, T> {
+    type Item = SegmentedSlice<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next == self.end {
+            return None;
+        }
+        let len = self.size.min(self.end - self.next);
+        self.next += len;
+        Some(SegmentedSlice {
+            list: self.list,
+            start: self.next - len,
+            len,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.end - self.next).div_ceil(self.size);
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T> DoubleEndedIterator for Chunks<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.next == self.end {
+            return None;
+        }
+        // only the last chunk may be shorter than size
+        let len = match (self.end - self.next) % self.size {
+            0 => self.size,
+            rest => rest,
+        };
+        self.end -= len;
+        Some(SegmentedSlice {
+            list: self.list,
+            start: self.end,
+            len,
+        })
+    }
+}
+
+impl<T> ExactSizeIterator for Chunks<'_, T> {}
+
+impl<T> std::iter::FusedIterator for Chunks<'_, T> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::alloc;
+
+    use super::*;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[global_allocator]
+    static A: alloc::SegmentedAlloc = alloc::SegmentedAlloc::new();
+
+    /// Records its id in the shared list once dropped, thus tests can check drop counts and order
+    struct DropCounter<'a>(usize, &'a RefCell<Vec<usize>>);
+    impl Drop for DropCounter<'_> {
+        fn drop(&mut self) {
+            self.1.borrow_mut().push(self.0);
+        }
+    }
+
+    #[test]
+    fn push_and_get_basic() {
+        let mut list = SegmentedList::new();
+
+        list.push(42);
+        list.push(100);
+        list.push(7);
+
+        assert_eq!(list.len, 3);
+        assert_eq!(list.get(0), Some(&42));
+        assert_eq!(list.get(1), Some(&100));
+        assert_eq!(list.get(2), Some(&7));
+        assert_eq!(list.get(3), None); // out of bounds
+    }
+
+    #[test]
+    fn push_and_get_mut() {
+        let mut list = SegmentedList::new();
+
+        list.push(42);
+        list.push(100);
+        list.push(7);
+
+        assert_eq!(list.len, 3);
+        assert_eq!(list.get(0), Some(&42));
+        assert_eq!(list.get(1), Some(&100));
+        assert_eq!(list.get(2), Some(&7));
+        assert_eq!(list.get(3), None);
+    }
+
+    #[test]
+    fn into_vec_flattens_correctly() {
+        let mut list = SegmentedList::new();
+
+        for i in 0..20 {
+            list.push(i);
+        }
+
+        let vec = list.to_vec();
+        assert_eq!(vec.len(), 20);
+        assert_eq!(vec, (0..20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn index_trait_returns_correct_values() {
+        let mut list = SegmentedList::new();
+        for i in 0..10 {
+            list.push(i * 2);
+        }
+        assert_eq!(list[0], 0);
+        assert_eq!(list[1], 2);
+        assert_eq!(list[9], 18);
+    }
+
+    #[test]
+    fn index_mut_trait_returns_correct_values() {
+        let mut list = SegmentedList::new();
+        for i in 0..10 {
+            list.push(i * 2);
+        }
+
+        assert_eq!(list[0], 0);
+        assert_eq!(list[1], 2);
+        assert_eq!(list[9], 18);
+
+        list[0] = 5;
+        list[1] = 3;
+        list[9] = 36;
+
+        assert_eq!(list[0], 5);
+        assert_eq!(list[1], 3);
+        assert_eq!(list[9], 36);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn index_panics_on_invalid() {
+        let mut list = SegmentedList::new();
+        list.push(1);
+        let _ = list[1]; // index 1 invalid (len = 1)
+    }
+
+    #[test]
+    fn works_across_blocks() {
+        let mut list = SegmentedList::new();
+
+        // Fill more than START_SIZE to force allocation of next block(s)
+        for i in 0..(START_SIZE + 5) {
+            list.push(i);
+        }
+
+        let vec = list.to_vec();
+        assert_eq!(vec, (0..(START_SIZE + 5)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn empty_list_into_vec() {
+        let list: SegmentedList<i32> = SegmentedList::new();
+        let vec = list.to_vec();
+        assert!(vec.is_empty());
+    }
+
+    #[test]
+    fn exact_block_boundaries() {
+        let mut list = SegmentedList::new();
+        let blocks_to_test = 3;
+        let mut total = 0;
+        for block_idx in 0..blocks_to_test {
+            let size = START_SIZE << block_idx;
+            for i in 0..size {
+                list.push(total + i);
+            }
+            total += size;
+            assert_eq!(list.len, total);
+        }
+        let vec = list.to_vec();
+        assert_eq!(vec, (0..total).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn drop_safety_test() {
+        let counter = Rc::new(RefCell::new(Vec::new()));
+        {
+            let mut list: SegmentedList<DropCounter> = SegmentedList::new();
+            for _ in 0..50 {
+                list.push(DropCounter(0, &counter));
+            }
+            // consuming the list should drop all elements exactly once
+            list.to_vec();
+        }
+        assert_eq!(counter.borrow().len(), 50);
+    }
+
+    #[test]
+    fn random_values_across_blocks() {
+        let mut list = SegmentedList::new();
+        // Push sparse and varied values
+        for i in (0..(START_SIZE * 5)).rev() {
+            // reverse order for variety
+            list.push(i * 3);
+        }
+        let vec = list.to_vec();
+        for (idx, val) in vec.iter().enumerate() {
+            assert_eq!(*val, (START_SIZE * 5 - 1 - idx) * 3);
+        }
+    }
+
+    #[test]
+    fn stress_test_large_fill() {
+        let mut list = SegmentedList::new();
+        let count = START_SIZE * 50; // large, spans many blocks
+        for i in 0..count {
+            list.push(i);
+        }
+        let vec = list.to_vec();
+        assert_eq!(vec.len(), count);
+        assert_eq!(vec[count - 1], count - 1);
+        assert_eq!(vec[0], 0);
+    }
+
+    #[test]
+    fn capacity_and_is_empty_work() {
+        let mut list = SegmentedList::new();
+        assert_eq!(list.capacity(), 8);
+        assert!(list.is_empty());
+
+        list.push(10);
+        assert!(!list.is_empty());
+        assert!(list.capacity() >= 1);
+    }
+
+    #[test]
+    fn first_and_last_work() {
+        let mut list = SegmentedList::new();
+        assert!(list.first().is_none());
+        assert!(list.last().is_none());
+
+        list.push(1);
+        list.push(2);
+        list.push(3);
+        assert_eq!(list.len(), 3);
+
+        assert_eq!(list.first(), Some(&1));
+        assert_eq!(list.last(), Some(&3));
+
+        *list.first_mut().unwrap() = 10;
+        *list.last_mut().unwrap() = 30;
+
+        assert_eq!(list.first(), Some(&10));
+        assert_eq!(list[1], 2);
+        assert_eq!(list.last(), Some(&30));
+    }
+
+    #[test]
+    fn clear_resets_len_and_drops_items() {
+        let counter = Rc::new(RefCell::new(Vec::new()));
+        let mut list: SegmentedList<DropCounter> = SegmentedList::new();
+
+        for _ in 0..10 {
+            list.push(DropCounter(0, &counter));
+        }
+        assert_eq!(list.len(), 10);
+        list.clear();
+        assert_eq!(list.len(), 0);
+        assert_eq!(counter.borrow().len(), 10);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn extend_trait_adds_items() {
+        let mut list = SegmentedList::new();
+        list.extend(vec![1, 2, 3]);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.to_vec(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn from_iterator_constructs_list() {
+        let list: SegmentedList<_> = (0..5).collect();
+        assert_eq!(list.len(), 5);
+        assert_eq!(list.to_vec(), (0..5).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn pop_returns_last_and_reuses_blocks() {
+        let mut list = SegmentedList::new();
+        assert_eq!(list.pop(), None);
+
+        for i in 0..(START_SIZE + 1) {
+            list.push(i);
+        }
+        let capacity = list.capacity();
+        let second_block = list.blocks[1];
+
+        // cycle around the boundary between block 0 and block 1
+        for _ in 0..3 {
+            assert_eq!(list.pop(), Some(START_SIZE));
+            assert_eq!(list.pop(), Some(START_SIZE - 1));
+            assert_eq!(list.len(), START_SIZE - 1);
+            list.push(START_SIZE - 1);
+            list.push(START_SIZE);
+        }
+
+        assert_eq!(list.capacity(), capacity);
+        assert_eq!(list.blocks[1], second_block);
+        assert_eq!(list.to_vec(), (0..(START_SIZE + 1)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn pop_moves_value_out_once() {
+        let counter = Rc::new(RefCell::new(Vec::new()));
+        let mut list: SegmentedList<DropCounter> = SegmentedList::new();
+        list.push(DropCounter(0, &counter));
+        list.push(DropCounter(0, &counter));
+
+        let popped = list.pop();
+        assert!(popped.is_some());
+        assert_eq!(counter.borrow().len(), 0);
+        drop(popped);
+        assert_eq!(counter.borrow().len(), 1);
+        assert_eq!(list.len(), 1);
+    }
+
+    #[test]
+    fn iter_yields_elements_in_order() {
+        let expected: Vec<usize> = (0..(START_SIZE * 5)).collect();
+        let list: SegmentedList<_> = expected.iter().copied().collect();
+        assert!(list.iter().zip(0..).all(|(v, i)| *v == expected[i]));
+        assert_eq!(list.iter().count(), expected.len());
+    }
+
+    #[test]
+    fn iter_mut_modifies_in_place() {
+        let mut list: SegmentedList<_> = (0..(START_SIZE * 3)).collect();
+        for v in list.iter_mut() {
+            *v += 1;
+        }
+        assert_eq!(list.to_vec(), (1..(START_SIZE * 3 + 1)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn into_iter_moves_elements_out() {
+        let list: SegmentedList<_> = (0..(START_SIZE * 3)).map(|i| i.to_string()).collect();
+        let mut count = 0;
+        for (i, s) in list.into_iter().enumerate() {
+            assert_eq!(s, i.to_string());
+            count += 1;
+        }
+        assert_eq!(count, START_SIZE * 3);
+    }
+
+    #[test]
+    fn iter_spans_many_blocks() {
+        let mut list = SegmentedList::new();
+        // START_SIZE * (1 + 2 + 4 + 8) fills the first four blocks, the rest spills into block 4
+        let count = START_SIZE * 15 + 3;
+        for i in 0..count {
+            list.push(i);
+        }
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            (0..count).collect::<Vec<_>>()
+        );
+
+        for v in list.iter_mut() {
+            *v *= 2;
+        }
+        assert!(list.iter().enumerate().all(|(i, v)| *v == i * 2));
+    }
+
+    #[test]
+    fn iter_reports_exact_size() {
+        let list: SegmentedList<_> = (0..(START_SIZE * 5)).collect();
+        let mut iter = list.iter();
+        assert_eq!(iter.len(), START_SIZE * 5);
+
+        for expected in 0..(START_SIZE * 5) {
+            assert_eq!(iter.next(), Some(&expected));
+            let remaining = START_SIZE * 5 - expected - 1;
+            assert_eq!(iter.size_hint(), (remaining, Some(remaining)));
+        }
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.len(), 0);
+    }
+
+    #[test]
+    fn into_iter_empty_list() {
+        let list: SegmentedList<String> = SegmentedList::new();
+        assert_eq!(list.into_iter().next(), None);
+    }
+
+    #[test]
+    fn into_iter_drops_unconsumed_elements() {
+        let counter = Rc::new(RefCell::new(Vec::new()));
+        let mut list: SegmentedList<DropCounter> = SegmentedList::new();
+        for _ in 0..50 {
+            list.push(DropCounter(0, &counter));
+        }
+
+        let mut iter = list.into_iter();
+        for _ in 0..20 {
+            drop(iter.next());
+        }
+        assert_eq!(counter.borrow().len(), 20);
+        // dropping half way through drops the remaining 30 exactly once
+        drop(iter);
+        assert_eq!(counter.borrow().len(), 50);
+    }
+
+    #[test]
+    fn iter_rev_walks_backwards() {
+        let empty: SegmentedList<usize> = SegmentedList::new();
+        assert_eq!(empty.iter().next_back(), None);
+
+        let mut single = SegmentedList::new();
+        single.push(1);
+        assert_eq!(single.iter().rev().collect::<Vec<_>>(), vec![&1]);
+
+        let count = START_SIZE * 7 + 2;
+        let mut list: SegmentedList<_> = (0..count).collect();
+        assert_eq!(
+            list.iter().rev().take(10).copied().collect::<Vec<_>>(),
+            (count - 10..count).rev().collect::<Vec<_>>()
+        );
+        assert_eq!(list.iter().rposition(|v| *v == 3), Some(3));
+
+        for v in list.iter_mut().rev() {
+            *v += 1;
+        }
+        assert_eq!(list.iter().rfind(|v| **v == 1), Some(&1));
+
+        let reversed: SegmentedList<_> = list.into_iter().rev().collect();
+        assert_eq!(reversed.to_vec(), (1..count + 1).rev().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn iter_ends_meet_on_block_boundary() {
+        // START_SIZE * 3 fills blocks 0 and 1 exactly, both ends meet at the start of block 1
+        let list: SegmentedList<_> = (0..(START_SIZE * 3)).collect();
+        let mut iter = list.iter();
+        for i in 0..START_SIZE {
+            assert_eq!(iter.next(), Some(&i));
+        }
+        for i in (START_SIZE..(START_SIZE * 3)).rev() {
+            assert_eq!(iter.next_back(), Some(&i));
+        }
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+    }
+
+    #[test]
+    fn drop_runs_element_destructors() {
+        let counter = Rc::new(RefCell::new(Vec::new()));
+        let mut list: SegmentedList<DropCounter> = SegmentedList::new();
+        for _ in 0..(START_SIZE * 4 + 1) {
+            list.push(DropCounter(0, &counter));
+        }
+        assert_eq!(counter.borrow().len(), 0);
+        drop(list);
+        assert_eq!(counter.borrow().len(), START_SIZE * 4 + 1);
+    }
+
+    #[test]
+    fn iterators_are_exact_size_and_fused() {
+        let mut list: SegmentedList<_> = (0..(START_SIZE * 3 + 1)).collect();
+
+        let mut iter_mut = list.iter_mut();
+        iter_mut.next_back();
+        assert_eq!(iter_mut.len(), START_SIZE * 3);
+
+        let mut into_iter = list.into_iter();
+        let mut v = Vec::with_capacity(into_iter.len());
+        v.extend(into_iter.by_ref().take(5));
+        assert_eq!(into_iter.len(), START_SIZE * 3 + 1 - 5);
+        v.extend(into_iter.by_ref());
+        assert_eq!(v, (0..(START_SIZE * 3 + 1)).collect::<Vec<_>>());
+
+        assert_eq!(into_iter.size_hint(), (0, Some(0)));
+        assert_eq!(into_iter.next(), None);
+        assert_eq!(into_iter.next_back(), None);
+        assert_eq!(into_iter.next(), None);
+    }
+
+    #[test]
+    fn iter_mut_doubles_every_element() {
+        let count = START_SIZE * 9 + 5;
+        let mut list: SegmentedList<_> = (0..count).collect();
+        // hold all mutable references at once, they must never alias
+        let refs: Vec<&mut usize> = list.iter_mut().collect();
+        assert_eq!(refs.len(), count);
+        for r in refs {
+            *r *= 2;
+        }
+        assert_eq!(list.to_vec(), (0..count).map(|i| i * 2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn pop_drains_to_empty() {
+        let count = START_SIZE * 7;
+        let mut list: SegmentedList<_> = (0..count).collect();
+        let mut expected = count;
+        // crosses back from the first slot of block 2 and block 1 into their predecessors
+        while let Some(v) = list.pop() {
+            expected -= 1;
+            assert_eq!(v, expected);
+            assert_eq!(list.len(), expected);
+            assert_eq!(list.last(), expected.checked_sub(1).as_ref());
+        }
+        assert_eq!(expected, 0);
+        assert!(list.is_empty());
+        assert_eq!(list.pop(), None);
+
+        list.push(42);
+        assert_eq!(list.pop(), Some(42));
+        assert_eq!(list.pop(), None);
+    }
+
+    #[test]
+    fn clone_non_copy_elements() {
+        let list: SegmentedList<String> =
+            (0..(START_SIZE * 3 + 3)).map(|i| i.to_string()).collect();
+        let mut cloned = list.clone();
+        assert_eq!(cloned.len(), list.len());
+        assert!(list.iter().zip(cloned.iter()).all(|(a, b)| a == b));
+
+        // the clone is independent and can keep growing
+        cloned.push("tail".to_string());
+        assert_eq!(cloned.last().map(String::as_str), Some("tail"));
+        assert_eq!(list.len(), START_SIZE * 3 + 3);
+    }
+
+    #[test]
+    fn clone_panic_drops_cloned_elements_once() {
+        struct PanickyClone<'a> {
+            id: usize,
+            drops: &'a RefCell<u32>,
+        }
+        impl<'a> Clone for PanickyClone<'a> {
+            fn clone(&self) -> Self {
+                if self.id == 10 {
+                    panic!("clone of {} failed", self.id);
+                }
+                PanickyClone {
+                    id: self.id,
+                    drops: self.drops,
+                }
+            }
+        }
+        impl<'a> Drop for PanickyClone<'a> {
+            fn drop(&mut self) {
+                *self.drops.borrow_mut() += 1;
+            }
+        }
+
+        let drops = RefCell::new(0);
+        let list: SegmentedList<_> = (0..20)
+            .map(|id| PanickyClone { id, drops: &drops })
+            .collect();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| list.clone()));
+        assert!(result.is_err());
+        // the ten clones made before the panic were dropped, the originals are untouched
+        assert_eq!(*drops.borrow(), 10);
+        drop(list);
+        assert_eq!(*drops.borrow(), 30);
+    }
+
+    #[test]
+    fn into_iter_partial_consumption_from_both_ends() {
+        let counter = Rc::new(RefCell::new(Vec::new()));
+        let len = START_SIZE * 6 + 1;
+        let list: SegmentedList<DropCounter> = (0..len).map(|_| DropCounter(0, &counter)).collect();
+
+        let mut iter = list.into_iter();
+        let front: Vec<_> = iter.by_ref().take(len / 4).collect();
+        let back: Vec<_> = iter.by_ref().rev().take(len / 4).collect();
+        // neither the list nor the iterator own the yielded elements anymore
+        drop(iter);
+        assert_eq!(counter.borrow().len(), len - front.len() - back.len());
+        drop(front);
+        drop(back);
+        assert_eq!(counter.borrow().len(), len);
+    }
+
+    #[test]
+    fn swap_within_and_across_blocks() {
+        let mut list: SegmentedList<_> = (0..(START_SIZE * 3)).map(|i| i.to_string()).collect();
+
+        list.swap(1, 5);
+        assert_eq!(list[1], "5");
+        assert_eq!(list[5], "1");
+
+        list.swap(0, START_SIZE * 2 + 1);
+        assert_eq!(list[0], (START_SIZE * 2 + 1).to_string());
+        assert_eq!(list[START_SIZE * 2 + 1], "0");
+
+        list.swap(3, 3);
+        assert_eq!(list[3], "3");
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn swap_panics_on_invalid() {
+        let mut list: SegmentedList<_> = (0..4).collect();
+        list.swap(0, 4);
+    }
+
+    #[test]
+    fn clone_partially_filled_last_block() {
+        let len = START_SIZE + 3;
+        let list: SegmentedList<u64> = (0..len as u64).collect();
+        let mut cloned = list.clone();
+
+        // only the initialized prefix is copied, the clone continues right after it
+        assert_eq!(cloned.len(), len);
+        cloned.push(1000);
+        assert_eq!(cloned.get(len), Some(&1000));
+        assert_eq!(
+            cloned.to_vec(),
+            (0..len as u64)
+                .chain(std::iter::once(1000))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(list.to_vec(), (0..len as u64).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn clone_lengths_around_block_boundaries() {
+        for len in [
+            1,
+            START_SIZE - 1,
+            START_SIZE,
+            START_SIZE + 1,
+            START_SIZE * 3 + 5,
+        ] {
+            let list: SegmentedList<_> = (0..len).map(|i| i.to_string()).collect();
+            let cloned = list.clone();
+            assert_eq!(cloned, list);
+            // the clone only allocates the blocks needed for its initialized prefix
+            assert_eq!(cloned.capacity(), list.capacity());
+        }
+    }
+
+    #[test]
+    fn insert_shifts_elements() {
+        let mut list: SegmentedList<_> = (0..(START_SIZE * 3)).map(|i| i.to_string()).collect();
+        let mut expected: Vec<_> = (0..(START_SIZE * 3)).map(|i| i.to_string()).collect();
+
+        for (idx, v) in [(0, "front"), (START_SIZE, "boundary"), (13, "middle")] {
+            list.insert(idx, v.to_string());
+            expected.insert(idx, v.to_string());
+        }
+        // the last element spills into a newly allocated block
+        let len = list.len();
+        list.insert(len, "end".to_string());
+        expected.insert(len, "end".to_string());
+
+        assert_eq!(list.len(), expected.len());
+        assert_eq!(list.to_vec(), expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "insertion index")]
+    fn insert_panics_past_len() {
+        let mut list: SegmentedList<_> = (0..4).collect();
+        list.insert(5, 0);
+    }
+
+    #[test]
+    fn retain_compacts_across_blocks() {
+        let counter = RefCell::new(Vec::new());
+        let len = START_SIZE * 7;
+        let mut list: SegmentedList<_> = (0..len).map(|i| DropCounter(i, &counter)).collect();
+        let capacity = list.capacity();
+
+        list.retain(|v| v.0 % 3 == 0);
+        assert_eq!(counter.borrow().len(), len - len.div_ceil(3));
+        assert_eq!(list.len(), len.div_ceil(3));
+        assert_eq!(list.capacity(), capacity);
+        assert!(list.iter().enumerate().all(|(i, v)| v.0 == i * 3));
+
+        // the freed up slots are reused by push
+        list.push(DropCounter(usize::MAX, &counter));
+        assert_eq!(list.last().map(|v| v.0), Some(usize::MAX));
+    }
+
+    #[test]
+    fn retain_panicking_predicate_keeps_list_consistent() {
+        let mut list: SegmentedList<_> = (0..(START_SIZE * 3)).map(|i| i.to_string()).collect();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            list.retain(|v| {
+                if v == "12" {
+                    panic!("predicate failed");
+                }
+                v.len() == 1
+            })
+        }));
+        assert!(result.is_err());
+        // 10..12 were removed, everything from the panic onwards is kept untouched
+        let expected: Vec<_> = (0..10)
+            .chain(12..(START_SIZE * 3))
+            .map(|i| i.to_string())
+            .collect();
+        assert_eq!(list.to_vec(), expected);
+    }
+
+    #[test]
+    fn remove_shifts_elements() {
+        let len = START_SIZE * 15;
+        let mut list: SegmentedList<_> = (0..len).collect();
+        let mut expected: Vec<_> = (0..len).collect();
+
+        // last element, first element, both sides of the boundary between block 1 and 2
+        for idx in [len - 1, 0, BLOCK_STARTS[2], BLOCK_STARTS[2] - 1] {
+            assert_eq!(list.remove(idx), expected.remove(idx));
+        }
+        assert_eq!(list.len(), expected.len());
+        assert_eq!(list.to_vec(), expected);
+    }
+
+    #[test]
+    fn remove_drops_nothing_but_the_returned_value() {
+        let counter = RefCell::new(Vec::new());
+        let mut list: SegmentedList<_> = (0..20).map(|i| DropCounter(i, &counter)).collect();
+        let removed = list.remove(10);
+        assert_eq!(removed.0, 10);
+        assert_eq!(counter.borrow().len(), 0);
+        drop(removed);
+        assert_eq!(counter.borrow().len(), 1);
+        assert!(list.iter().map(|v| v.0).eq((0..10).chain(11..20)));
+        drop(list);
+        assert_eq!(counter.borrow().len(), 20);
+    }
+
+    #[test]
+    #[should_panic(expected = "index 4 out of bounds for List of length 4")]
+    fn remove_panics_on_invalid() {
+        let mut list: SegmentedList<_> = (0..4).collect();
+        list.remove(4);
+    }
+
+    #[test]
+    fn pop_then_push_reuses_slots_without_leaks() {
+        let counter = RefCell::new(Vec::new());
+        let mut list = SegmentedList::new();
+        for i in 0..(START_SIZE + 2) {
+            list.push(DropCounter(i, &counter));
+        }
+
+        // pop back below the first block boundary
+        for _ in 0..4 {
+            drop(list.pop());
+        }
+        assert_eq!(counter.borrow().len(), 4);
+        assert_eq!(list.last().map(|v| v.0), Some(START_SIZE - 3));
+        list.last_mut().unwrap().0 = 100;
+
+        for i in 0..4 {
+            list.push(DropCounter(200 + i, &counter));
+        }
+        assert_eq!(list.len(), START_SIZE + 2);
+        assert_eq!(list[START_SIZE - 3].0, 100);
+        assert_eq!(list.last().map(|v| v.0), Some(203));
+        assert_eq!(counter.borrow().len(), 4);
+
+        drop(list);
+        assert_eq!(counter.borrow().len(), START_SIZE + 2 + 4);
+    }
+
+    #[test]
+    fn retain_mut_mutates_survivors() {
+        let mut list: SegmentedList<_> = (0..(START_SIZE * 4)).collect();
+        list.retain_mut(|v| {
+            *v *= 10;
+            *v % 20 == 0
+        });
+        assert_eq!(
+            list.to_vec(),
+            (0..(START_SIZE * 4))
+                .step_by(2)
+                .map(|v| v * 10)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn swap_remove_moves_last_into_place() {
+        // three blocks, the last one partially filled
+        let len = BLOCK_STARTS[2] + 5;
+        let mut list: SegmentedList<_> = (0..len).collect();
+
+        assert_eq!(list.swap_remove(10), 10);
+        assert_eq!(list[10], len - 1);
+        assert_eq!(list.len(), len - 1);
+
+        // removing the last element needs no swap
+        assert_eq!(list.swap_remove(len - 2), len - 2);
+        assert_eq!(list.len(), len - 2);
+        assert_eq!(list.last(), Some(&(len - 3)));
+    }
+
+    #[test]
+    #[should_panic(expected = "swap_remove index")]
+    fn swap_remove_panics_on_invalid() {
+        let mut list: SegmentedList<usize> = SegmentedList::new();
+        list.swap_remove(0);
+    }
+
+    #[test]
+    fn truncate_drops_tail_across_blocks() {
+        let counter = RefCell::new(Vec::new());
+        let len = START_SIZE * 15;
+        let mut list: SegmentedList<_> = (0..len).map(|i| DropCounter(i, &counter)).collect();
+
+        list.truncate(len);
+        assert_eq!(counter.borrow().len(), 0);
+
+        // exactly onto the boundary between block 1 and 2
+        list.truncate(BLOCK_STARTS[2]);
+        assert_eq!(counter.borrow().len(), len - BLOCK_STARTS[2]);
+        assert_eq!(list.len(), BLOCK_STARTS[2]);
+        assert_eq!(list.last().map(|v| v.0), Some(BLOCK_STARTS[2] - 1));
+
+        list.push(DropCounter(usize::MAX, &counter));
+        assert_eq!(list[BLOCK_STARTS[2]].0, usize::MAX);
+
+        list.truncate(0);
+        assert!(list.is_empty());
+        assert_eq!(counter.borrow().len(), len + 1);
+    }
+
+    #[test]
+    fn push_after_clear_starts_at_front() {
+        let mut list: SegmentedList<_> = (0..(START_SIZE * 2)).collect();
+        list.clear();
+        list.push(7);
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0], 7);
+        assert_eq!(list.to_vec(), vec![7]);
+    }
+
+    #[test]
+    fn insert_keeps_list_sorted() {
+        let mut list = SegmentedList::new();
+        let mut expected = Vec::new();
+        // deterministic but scattered insertion order
+        for i in 0..(START_SIZE * 8) {
+            let v = (i * 37) % 101;
+            let idx = list.iter().position(|x| *x > v).unwrap_or(list.len());
+            list.insert(idx, v);
+            expected.push(v);
+        }
+        expected.sort();
+        assert_eq!(list.to_vec(), expected);
+    }
+
+    #[test]
+    fn debug_formats_like_a_slice() {
+        let empty: SegmentedList<i32> = SegmentedList::new();
+        assert_eq!(format!("{:?}", empty), "[]");
+
+        let list: SegmentedList<_> = (0..(START_SIZE + 3)).collect();
+        let vec: Vec<_> = (0..(START_SIZE + 3)).collect();
+        assert_eq!(format!("{:?}", list), format!("{:?}", vec));
+
+        let alternate = format!("{:#?}", list);
+        assert!(alternate.contains("len: 11"));
+        assert!(alternate.contains("capacity: 16"));
+        assert!(alternate.contains("used: 3"));
+    }
+
+    #[test]
+    fn truncate_fifty_to_ten() {
+        let counter = RefCell::new(Vec::new());
+        let mut list: SegmentedList<_> = (0..50).map(|_| DropCounter(0, &counter)).collect();
+        let capacity = list.capacity();
+        list.truncate(10);
+        assert_eq!(counter.borrow().len(), 40);
+        assert_eq!(list.len(), 10);
+        // the blocks are kept around for the next pass
+        assert_eq!(list.capacity(), capacity);
+    }
+
+    #[test]
+    fn remove_until_empty() {
+        let len = START_SIZE * 7;
+        let mut list: SegmentedList<_> = (0..len).map(|i| i.to_string()).collect();
+        let mut expected: Vec<_> = (0..len).map(|i| i.to_string()).collect();
+        while !expected.is_empty() {
+            // alternate between the front and the middle to shift across every block boundary
+            let idx = if expected.len() % 2 == 0 {
+                0
+            } else {
+                expected.len() / 2
+            };
+            assert_eq!(list.remove(idx), expected.remove(idx));
+            assert_eq!(list.len(), expected.len());
+        }
+        assert!(list.is_empty());
+        list.push("again".to_string());
+        assert_eq!(list.to_vec(), vec!["again".to_string()]);
+    }
+
+    #[test]
+    fn eq_compares_len_and_elements() {
+        let len = BLOCK_STARTS[3] + 2;
+        let mut pushed = SegmentedList::new();
+        for i in 0..len {
+            pushed.push(i);
+        }
+        let mut extended = SegmentedList::new();
+        extended.extend(0..len);
+        assert!(pushed == extended);
+
+        let shorter: SegmentedList<_> = (0..len - 1).collect();
+        assert!(pushed != shorter);
+
+        // same length, differing last element past the boundary of block 2 and 3
+        *extended.last_mut().unwrap() = 0;
+        assert!(pushed != extended);
+
+        let empty: SegmentedList<usize> = SegmentedList::new();
+        assert!(empty == SegmentedList::new());
+    }
+
+    #[test]
+    fn retain_even_numbers() {
+        let mut list: SegmentedList<_> = (0..100).collect();
+        list.retain(|v| v % 2 == 0);
+        assert_eq!(list.len(), 50);
+        assert_eq!(list.to_vec(), (0..100).step_by(2).collect::<Vec<_>>());
+
+        let dropped = RefCell::new(Vec::new());
+        let mut list: SegmentedList<_> = (0..100).map(|i| DropCounter(i, &dropped)).collect();
+        list.retain(|v| v.0 % 2 == 0);
+        assert_eq!(*dropped.borrow(), (1..100).step_by(2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn swap_remove_matches_vec() {
+        let len = START_SIZE * 7 + 3;
+        let mut list: SegmentedList<_> = (0..len).collect();
+        let mut expected: Vec<_> = (0..len).collect();
+        let mut i = 0;
+        while !expected.is_empty() {
+            let idx = (i * 7) % expected.len();
+            assert_eq!(list.swap_remove(idx), expected.swap_remove(idx));
+            i += 1;
+        }
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn eq_against_vec_slices_and_arrays() {
+        let empty: SegmentedList<i32> = SegmentedList::new();
+        assert_eq!(empty, Vec::<i32>::new());
+        assert_eq!(empty, &[0i32; 0][..]);
+        assert_eq!(empty, [0i32; 0]);
+
+        let len = START_SIZE * 15 + 4;
+        let list: SegmentedList<_> = (0..len).collect();
+        let vec: Vec<_> = (0..len).collect();
+        assert_eq!(list, vec);
+        assert_eq!(vec, list);
+        assert_eq!(list, vec.as_slice());
+        assert_eq!(vec.as_slice(), list);
+
+        let small: SegmentedList<_> = (1..=3).collect();
+        assert_eq!(small, [1, 2, 3]);
+        assert_eq!([1, 2, 3], small);
+        assert_ne!(small, [1, 2]);
+        assert_ne!(small, vec![1, 2, 4]);
+        assert_ne!(vec![1, 2, 3, 4], small);
+    }
+
+    #[test]
+    fn debug_matches_vec_for_multi_block_lists() {
+        for len in [1, START_SIZE, START_SIZE + 1, BLOCK_STARTS[4] + 7] {
+            let list: SegmentedList<_> = (0..len).map(|i| format!("v{}", i)).collect();
+            let vec: Vec<_> = (0..len).map(|i| format!("v{}", i)).collect();
+            assert_eq!(format!("{:?}", list), format!("{:?}", vec));
+        }
+
+        #[derive(Debug)]
+        #[allow(dead_code)]
+        struct Holder {
+            list: SegmentedList<u8>,
+        }
+        let holder = Holder {
+            list: (1..=3).collect(),
+        };
+        assert_eq!(format!("{:?}", holder), "Holder { list: [1, 2, 3] }");
+    }
+
+    #[test]
+    fn truncate_keeps_dropping_after_a_panicking_destructor() {
+        struct PanicOnDrop<'a>(usize, &'a RefCell<u32>);
+        impl<'a> Drop for PanicOnDrop<'a> {
+            fn drop(&mut self) {
+                *self.1.borrow_mut() += 1;
+                if self.0 == START_SIZE + 1 {
+                    panic!("drop of {} failed", self.0);
+                }
+            }
+        }
+
+        let counter = RefCell::new(0);
+        let len = BLOCK_STARTS[4];
+        let mut list: SegmentedList<_> = (0..len).map(|i| PanicOnDrop(i, &counter)).collect();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| list.truncate(4)));
+        assert!(result.is_err());
+        // every element of the tail was dropped, including the later blocks
+        assert_eq!(*counter.borrow() as usize, len - 4);
+        assert_eq!(list.len(), 4);
+    }
+
+    #[test]
+    fn hash_ignores_how_the_list_was_built() {
+        use std::hash::{BuildHasher, RandomState};
+
+        let state = RandomState::new();
+        let len = BLOCK_STARTS[3] + 1;
+
+        let mut pushed = SegmentedList::new();
+        for i in 0..len {
+            pushed.push(i as u8);
+        }
+        let collected: SegmentedList<u8> = (0..len).map(|i| i as u8).collect();
+        assert_eq!(state.hash_one(&pushed), state.hash_one(&collected));
+
+        let mut popped = collected.clone();
+        popped.pop();
+        assert_ne!(state.hash_one(&popped), state.hash_one(&collected));
+    }
+
+    #[test]
+    fn eq_lists_built_differently() {
+        let len = BLOCK_STARTS[5] + 3;
+        let mut extended: SegmentedList<String> = SegmentedList::new();
+        extended.extend((0..len).map(|i| i.to_string()));
+        let mut pushed = SegmentedList::new();
+        for i in 0..len {
+            pushed.push(i.to_string());
+        }
+        assert_eq!(extended, pushed);
+
+        pushed[BLOCK_STARTS[4]].push('!');
+        assert_ne!(extended, pushed);
+    }
+
+    #[test]
+    fn resize_grows_and_shrinks() {
+        let mut list: SegmentedList<String> = SegmentedList::new();
+        list.resize(START_SIZE + 2, "a".to_string());
+        assert_eq!(list, vec!["a".to_string(); START_SIZE + 2]);
+
+        list.resize(3, "b".to_string());
+        assert_eq!(list, vec!["a".to_string(); 3]);
+
+        list.resize(5, "b".to_string());
+        assert_eq!(list, ["a", "a", "a", "b", "b"].map(String::from));
+    }
+
+    #[test]
+    fn resize_with_calls_closure_per_new_element() {
+        struct NotClone(usize);
+
+        let mut next = 0;
+        let mut list = SegmentedList::new();
+        list.resize_with(BLOCK_STARTS[2] + 1, || {
+            next += 1;
+            NotClone(next)
+        });
+        assert_eq!(next, BLOCK_STARTS[2] + 1);
+        assert!(list.iter().enumerate().all(|(i, v)| v.0 == i + 1));
+
+        list.resize_with(2, || unreachable!());
+        assert_eq!(list.len(), 2);
+    }
+
+    #[test]
+    fn ordering_is_lexicographic() {
+        let ab: SegmentedList<_> = "ab".chars().collect();
+        let abc: SegmentedList<_> = "abc".chars().collect();
+        assert!(ab < abc);
+        assert!(abc > ab);
+
+        let len = BLOCK_STARTS[3] + 4;
+        let vec: Vec<_> = (0..len).collect();
+        let list: SegmentedList<_> = vec.iter().copied().collect();
+        for idx in [0, START_SIZE - 1, START_SIZE, BLOCK_STARTS[3], len - 1] {
+            for delta in [-1i64, 1] {
+                let mut other_vec = vec.clone();
+                other_vec[idx] = (other_vec[idx] as i64 + delta) as usize;
+                let other: SegmentedList<_> = other_vec.iter().copied().collect();
+                assert_eq!(list.cmp(&other), vec.cmp(&other_vec));
+                assert_eq!(list.partial_cmp(&other), vec.partial_cmp(&other_vec));
+            }
+        }
+
+        let prefix: SegmentedList<_> = (0..START_SIZE).collect();
+        assert_eq!(prefix.cmp(&list), std::cmp::Ordering::Less);
+        assert_eq!(list.cmp(&list.clone()), std::cmp::Ordering::Equal);
+
+        let nan: SegmentedList<f64> = [1.0, f64::NAN].into_iter().collect();
+        assert_eq!(nan.partial_cmp(&nan.clone()), None);
+    }
+
+    #[test]
+    fn clone_hundred_strings() {
+        let list: SegmentedList<String> = (0..100).map(|i| format!("entry {}", i)).collect();
+        let cloned = list.clone();
+        assert_eq!(cloned.len(), 100);
+        for (a, b) in list.iter().zip(cloned.iter()) {
+            assert_eq!(a, b);
+            // deep copies, not shared buffers
+            assert_ne!(a.as_ptr(), b.as_ptr());
+        }
+    }
+
+    #[test]
+    fn fill_overwrites_without_changing_len() {
+        let mut list: SegmentedList<_> = (0..(START_SIZE * 3 + 1)).collect();
+        list.fill(7);
+        assert_eq!(list, vec![7; START_SIZE * 3 + 1]);
+
+        let empty: &mut SegmentedList<usize> = &mut SegmentedList::new();
+        empty.fill(1);
+        assert!(empty.is_empty());
+
+        let counter = RefCell::new(Vec::new());
+        let mut list: SegmentedList<_> = (0..20).map(|i| DropCounter(i, &counter)).collect();
+        let mut next = 100;
+        list.fill_with(|| {
+            next += 1;
+            DropCounter(next, &counter)
+        });
+        assert_eq!(counter.borrow().len(), 20);
+        assert_eq!(list.len(), 20);
+        assert!(list.iter().enumerate().all(|(i, v)| v.0 == 101 + i));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_json_round_trip() {
+        for len in [0, START_SIZE / 2, START_SIZE * 7 + 3] {
+            let list: SegmentedList<_> = (0..len).map(|i| format!("elem {}", i)).collect();
+            let json = serde_json::to_string(&list).unwrap();
+            assert_eq!(
+                json,
+                serde_json::to_string(&(0..len).map(|i| format!("elem {}", i)).collect::<Vec<_>>())
+                    .unwrap()
+            );
+
+            let back: SegmentedList<String> = serde_json::from_str(&json).unwrap();
+            assert_eq!(back, list);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_json_rejects_non_sequences() {
+        assert!(serde_json::from_str::<SegmentedList<u8>>("{\"a\": 1}").is_err());
+        assert!(serde_json::from_str::<SegmentedList<u8>>("[1, \"two\"]").is_err());
+    }
+
+    #[test]
+    fn from_vec_moves_elements_once() {
+        let len = 100_000;
+        let counter = RefCell::new(Vec::new());
+        let vec: Vec<_> = (0..len).map(|i| DropCounter(i, &counter)).collect();
+        let list = SegmentedList::from(vec);
+        assert_eq!(counter.borrow().len(), 0);
+        assert_eq!(list.len(), len);
+        assert!(list.iter().enumerate().all(|(i, v)| v.0 == i));
+
+        drop(list);
+        assert_eq!(counter.borrow().len(), len);
+    }
+
+    #[test]
+    fn from_slice_clones_elements() {
+        let empty: SegmentedList<String> = SegmentedList::from(&[][..]);
+        assert!(empty.is_empty());
+
+        let source: Vec<_> = (0..(START_SIZE * 5 + 2)).map(|i| i.to_string()).collect();
+        let mut list = SegmentedList::from(&source[..]);
+        assert_eq!(list, source);
+        assert!(list.capacity() >= source.len());
+
+        list.push("after".into());
+        assert_eq!(list.last().map(String::as_str), Some("after"));
+    }
+
+    #[test]
+    fn clone_calls_clone_on_every_element() {
+        let list: SegmentedList<_> = (0..(START_SIZE * 3 + 2)).map(Rc::new).collect();
+        let cloned = list.clone();
+        assert!(list.iter().all(|rc| Rc::strong_count(rc) == 2));
+        assert!(
+            list.iter()
+                .zip(cloned.iter())
+                .all(|(a, b)| Rc::ptr_eq(a, b))
+        );
+
+        drop(cloned);
+        assert!(list.iter().all(|rc| Rc::strong_count(rc) == 1));
+    }
+
+    #[test]
+    fn with_capacity_allocates_up_front() {
+        let list: SegmentedList<u8> = SegmentedList::with_capacity(0);
+        assert_eq!(list.allocated_blocks(), 1);
+        assert!(list.is_empty());
+
+        let mut list = SegmentedList::with_capacity(1000);
+        assert!(list.capacity() >= 1000);
+        assert!(list.is_empty());
+        let blocks = list.allocated_blocks();
+        for i in 0..1000 {
+            list.push(i);
+            assert_eq!(list.allocated_blocks(), blocks);
+        }
+        assert_eq!(list, (0..1000).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn eq_ignores_spare_capacity() {
+        let mut reserved = SegmentedList::with_capacity(1000);
+        reserved.extend(0..(START_SIZE * 3));
+        let mut shrunk: SegmentedList<_> = (0..500).collect();
+        shrunk.truncate(START_SIZE * 3);
+        let fresh: SegmentedList<_> = (0..(START_SIZE * 3)).collect();
+        assert_eq!(reserved, fresh);
+        assert_eq!(shrunk, fresh);
+
+        // a difference in the very last element of a later block is still found
+        *shrunk.last_mut().unwrap() += 1;
+        assert_ne!(shrunk, fresh);
+    }
+
+    #[test]
+    fn reserve_allocates_missing_blocks_only() {
+        let mut list: SegmentedList<_> = (0..10).collect();
+        let blocks = list.allocated_blocks();
+        list.reserve(0);
+        list.reserve(START_SIZE * 2 - 10);
+        assert_eq!(list.allocated_blocks(), blocks);
+
+        list.reserve(100_000);
+        assert!(list.capacity() >= 100_010);
+        let blocks = list.allocated_blocks();
+        let first = list.first().unwrap() as *const i32;
+        for i in 10..100_010 {
+            list.push(i);
+        }
+        assert_eq!(list.allocated_blocks(), blocks);
+        assert_eq!(list.first().unwrap() as *const i32, first);
+        assert!(list.iter().copied().eq(0..100_010));
+    }
+
+    #[test]
+    fn with_capacity_pushes_keep_block_pointers() {
+        for n in [1, START_SIZE, START_SIZE + 1, 4096] {
+            let mut list = SegmentedList::with_capacity(n);
+            let blocks = list.blocks;
+            for i in 0..n {
+                list.push(i);
+            }
+            assert_eq!(list.blocks, blocks);
+            assert_eq!(list.len(), n);
+        }
+    }
+
+    #[test]
+    // the allocator's UnsafeCell doesn't take part in the ordering
+    #[allow(clippy::mutable_key_type)]
+    fn lists_order_inside_btreeset() {
+        let empty: SegmentedList<u32> = SegmentedList::new();
+        assert_eq!(empty.cmp(&SegmentedList::new()), std::cmp::Ordering::Equal);
+        assert!(empty < [0].into_iter().collect());
+
+        let set: std::collections::BTreeSet<SegmentedList<u32>> = [
+            vec![2],
+            vec![1, 2, 3],
+            vec![],
+            (0..40).collect(),
+            vec![1, 2],
+            vec![1, 2],
+        ]
+        .into_iter()
+        .map(SegmentedList::from)
+        .collect();
+        let ordered: Vec<Vec<u32>> = set.into_iter().map(SegmentedList::to_vec).collect();
+        assert_eq!(
+            ordered,
+            vec![
+                vec![],
+                (0..40).collect(),
+                vec![1, 2],
+                vec![1, 2, 3],
+                vec![2]
+            ]
+        );
+    }
+
+    #[test]
+    fn extend_from_slice_spans_blocks() {
+        let mut list: SegmentedList<u32> = (0..5).collect();
+        list.extend_from_slice(&[]);
+        assert_eq!(list.len(), 5);
+
+        // starts in block 0 and ends in block 2
+        let slice: Vec<u32> = (5..(BLOCK_STARTS[2] as u32 + 3)).collect();
+        list.extend_from_slice(&slice);
+        assert_eq!(list.len(), BLOCK_STARTS[2] + 3);
+        assert!(list.iter().copied().eq(0..(BLOCK_STARTS[2] as u32 + 3)));
+
+        list.push(1000);
+        list.extend_from_slice(&[1001, 1002]);
+        assert_eq!(list.pop(), Some(1002));
+        assert_eq!(list[BLOCK_STARTS[2] + 3], 1000);
+    }
+
+    #[test]
+    fn reserve_exact_across_block_sizes() {
+        let mut list: SegmentedList<u16> = SegmentedList::new();
+        assert_eq!(list.capacity(), START_SIZE);
+        list.reserve_exact(START_SIZE);
+        assert_eq!(list.capacity(), START_SIZE);
+
+        let mut expected = START_SIZE;
+        for (block, start) in BLOCK_STARTS.iter().enumerate().take(6).skip(1) {
+            list.reserve_exact(start + 1);
+            expected += START_SIZE << block;
+            assert_eq!(list.capacity(), expected);
+        }
+
+        list.extend(0..(BLOCK_STARTS[5] as u16 + 1));
+        let blocks = list.blocks;
+        list.reserve_exact(list.capacity() - list.len());
+        list.reserve_exact(0);
+        assert_eq!(list.capacity(), expected);
+        while list.len() < list.capacity() - 1 {
+            list.push(7);
+        }
+        assert_eq!(list.blocks, blocks);
+    }
+
+    #[test]
+    fn hash_matches_vec() {
+        use std::hash::{BuildHasher, RandomState};
+
+        let state = RandomState::new();
+        for len in [0, 1, START_SIZE, BLOCK_STARTS[4] + 5] {
+            let bytes: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            let list = SegmentedList::from(&bytes[..]);
+            assert_eq!(state.hash_one(&list), state.hash_one(&bytes));
+
+            let strings: Vec<String> = (0..len).map(|i| i.to_string()).collect();
+            let list = SegmentedList::from(&strings[..]);
+            assert_eq!(state.hash_one(&list), state.hash_one(&strings));
+        }
+    }
+
+    #[test]
+    fn append_moves_every_element_once() {
+        let counter = RefCell::new(Vec::new());
+        let mut empty = SegmentedList::new();
+        let mut other: SegmentedList<_> = (0..3).map(|i| DropCounter(i, &counter)).collect();
+        empty.append(&mut other);
+        assert!(other.is_empty());
+        assert_eq!(empty.len(), 3);
+
+        // the appended elements cross from block 0 into block 2
+        let mut list: SegmentedList<_> = (0..5).map(|i| DropCounter(i, &counter)).collect();
+        let mut other: SegmentedList<_> = (5..(BLOCK_STARTS[2] + 2))
+            .map(|i| DropCounter(i, &counter))
+            .collect();
+        list.append(&mut other);
+        assert!(other.is_empty());
+        assert_eq!(list.len(), BLOCK_STARTS[2] + 2);
+        assert!(list.iter().enumerate().all(|(i, v)| v.0 == i));
+        assert_eq!(counter.borrow().len(), 0);
+
+        // other stays usable
+        other.push(DropCounter(0, &counter));
+        drop(other);
+        drop(empty);
+        drop(list);
+        assert_eq!(counter.borrow().len(), 1 + 3 + BLOCK_STARTS[2] + 2);
+    }
+
+    #[test]
+    fn append_strings_and_empty_lists() {
+        let mut list: SegmentedList<String> = SegmentedList::new();
+        let mut empty: SegmentedList<String> = SegmentedList::new();
+        list.append(&mut empty);
+        assert!(list.is_empty() && empty.is_empty());
+
+        let mut other: SegmentedList<_> = (0..BLOCK_STARTS[2] + 1).map(|i| i.to_string()).collect();
+        list.append(&mut other);
+        assert!(other.is_empty());
+        assert_eq!(list.len(), BLOCK_STARTS[2] + 1);
+        list.append(&mut empty);
+        assert_eq!(list.len(), BLOCK_STARTS[2] + 1);
+
+        // appending mid block, the destination blocks are allocated before anything moves
+        other.extend((0..BLOCK_STARTS[3]).map(|i| format!("other {i}")));
+        list.append(&mut other);
+        assert_eq!(list.capacity(), BLOCK_STARTS[4]);
+        let expected: Vec<_> = (0..BLOCK_STARTS[2] + 1)
+            .map(|i| i.to_string())
+            .chain((0..BLOCK_STARTS[3]).map(|i| format!("other {i}")))
+            .collect();
+        assert_eq!(list, expected);
+
+        other.push("reused".into());
+        assert_eq!(other, ["reused"]);
+    }
+
+    #[test]
+    fn retain_every_other_of_three_blocks_drops_once() {
+        // exactly fills blocks 0, 1 and 2
+        let len = BLOCK_STARTS[3];
+        let dropped = RefCell::new(Vec::new());
+        let mut list: SegmentedList<_> = (0..len).map(|i| DropCounter(i, &dropped)).collect();
+        list.retain(|v| v.0 % 2 == 1);
+        assert_eq!(list.len(), len / 2);
+        assert!(list.iter().enumerate().all(|(i, v)| v.0 == i * 2 + 1));
+        assert_eq!(*dropped.borrow(), (0..len).step_by(2).collect::<Vec<_>>());
+
+        // a panic half way through keeps every element accounted for exactly once
+        dropped.borrow_mut().clear();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            list.retain(|v| {
+                assert!(v.0 < len / 2, "predicate failed");
+                v.0 % 4 == 1
+            })
+        }));
+        assert!(result.is_err());
+        drop(list);
+        let mut dropped = dropped.into_inner();
+        dropped.sort_unstable();
+        assert_eq!(dropped, (1..len).step_by(2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn debug_alternate_shows_blocks() {
+        let mut list: SegmentedList<u8> = SegmentedList::with_capacity(START_SIZE + 1);
+        list.extend(0..2);
+        let expected = r"SegmentedList {
+    len: 2,
+    blocks: [
+        Block {
+            index: 0,
+            capacity: 8,
+            used: 2,
+            elements: [
+                0,
+                1,
+            ],
+        },
+        Block {
+            index: 1,
+            capacity: 16,
+            used: 0,
+            elements: [],
+        },
+    ],
+}";
+        assert_eq!(format!("{:#?}", list), expected);
+    }
+
+    #[test]
+    fn contains_scans_initialized_elements() {
+        let mut list: SegmentedList<_> = (0..(BLOCK_STARTS[3] + 2)).collect();
+        assert!(list.contains(&0));
+        assert!(list.contains(&(BLOCK_STARTS[3] + 1)));
+        assert!(!list.contains(&(BLOCK_STARTS[3] + 2)));
+
+        // popped slots still hold stale bytes but are no longer part of the list
+        list.pop();
+        assert!(!list.contains(&(BLOCK_STARTS[3] + 1)));
+        assert!(!SegmentedList::new().contains(&0));
+    }
+
+    #[test]
+    fn retain_mut_evicts_and_updates_in_one_pass() {
+        struct Object<'a> {
+            age: u32,
+            alive: bool,
+            drops: &'a RefCell<Vec<usize>>,
+            id: usize,
+        }
+        impl<'a> Drop for Object<'a> {
+            fn drop(&mut self) {
+                self.drops.borrow_mut().push(self.id);
+            }
+        }
+
+        let drops = RefCell::new(Vec::new());
+        let len = BLOCK_STARTS[3] + 3;
+        let mut list: SegmentedList<_> = (0..len)
+            .map(|id| Object {
+                age: 0,
+                alive: id % 3 != 0,
+                drops: &drops,
+                id,
+            })
+            .collect();
+        list.retain_mut(|o| {
+            o.age += 1;
+            o.alive
+        });
+
+        let expected: Vec<_> = (0..len).filter(|id| id % 3 != 0).collect();
+        assert_eq!(list.iter().map(|o| o.id).collect::<Vec<_>>(), expected);
+        assert!(list.iter().all(|o| o.age == 1));
+        assert_eq!(*drops.borrow(), (0..len).step_by(3).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn display_formats_elements() {
+        let empty: SegmentedList<u8> = SegmentedList::new();
+        assert_eq!(empty.to_string(), "[]");
+
+        let list: SegmentedList<_> = (0..(START_SIZE + 2)).collect();
+        assert_eq!(list.to_string(), "[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]");
+
+        let list: SegmentedList<_> = ["a", "b"].into_iter().collect();
+        assert_eq!(format!("error in {}", list), "error in [a, b]");
+        assert_eq!(format!("{:>2}", list), "[ a,  b]");
+    }
+
+    #[test]
+    fn binary_search_matches_slice() {
+        let empty: SegmentedList<u32> = SegmentedList::new();
+        assert_eq!(empty.binary_search(&1), Err(0));
+
+        let vec: Vec<u32> = (0..(BLOCK_STARTS[4] as u32 + 5)).map(|i| i * 2).collect();
+        let list = SegmentedList::from(&vec[..]);
+        for x in 0..(vec.len() as u32 * 2 + 2) {
+            assert_eq!(
+                list.binary_search(&x),
+                vec.binary_search(&x),
+                "searching {}",
+                x
+            );
+        }
+
+        let dups: SegmentedList<u32> = [1, 2, 2, 2, 3].into_iter().collect();
+        assert!(matches!(dups.binary_search(&2), Ok(1..=3)));
+        assert_eq!(dups.binary_search(&0), Err(0));
+        assert_eq!(dups.binary_search(&4), Err(5));
+    }
+
+    #[test]
+    fn drain_straddling_blocks() {
+        let len = BLOCK_STARTS[3] + 5;
+        let mut list: SegmentedList<_> = (0..len).map(|i| i.to_string()).collect();
+        let mut vec: Vec<_> = (0..len).map(|i| i.to_string()).collect();
+
+        // from block 0 into block 2
+        let range = START_SIZE - 2..BLOCK_STARTS[2] + 3;
+        assert!(list.drain(range.clone()).eq(vec.drain(range)));
+        assert_eq!(list, vec);
+
+        // consumed from both ends, then dropped half way
+        let mut drain = list.drain(2..=20);
+        let mut expected = vec.drain(2..=20);
+        assert_eq!(drain.next(), expected.next());
+        assert_eq!(drain.next_back(), expected.next_back());
+        assert_eq!(drain.len(), expected.len());
+        drop(drain);
+        drop(expected);
+        assert_eq!(list, vec);
+
+        // the list keeps working after draining
+        list.push("end".into());
+        vec.push("end".into());
+        assert_eq!(list, vec);
+
+        assert!(list.drain(..).eq(vec.drain(..)));
+        assert!(list.is_empty());
+        list.push("again".into());
+        assert_eq!(list, ["again"]);
+    }
+
+    #[test]
+    fn drain_drops_unconsumed_elements_once() {
+        let dropped = RefCell::new(Vec::new());
+        let len = BLOCK_STARTS[2] + 1;
+        let mut list: SegmentedList<_> = (0..len).map(|i| DropCounter(i, &dropped)).collect();
+        let mut drain = list.drain(4..START_SIZE * 2);
+        assert_eq!(drain.next().map(|v| v.0), Some(4));
+        drop(drain);
+        assert_eq!(*dropped.borrow(), (4..START_SIZE * 2).collect::<Vec<_>>());
+        let remaining: Vec<_> = (0..4).chain(START_SIZE * 2..len).collect();
+        assert_eq!(list.iter().map(|v| v.0).collect::<Vec<_>>(), remaining);
+    }
+
+    #[test]
+    fn drain_stays_consistent_after_a_panicking_destructor() {
+        struct PanicOnDrop<'a>(usize, &'a RefCell<Vec<usize>>);
+        impl<'a> Drop for PanicOnDrop<'a> {
+            fn drop(&mut self) {
+                self.1.borrow_mut().push(self.0);
+                if self.0 == START_SIZE + 1 {
+                    panic!("drop of {} failed", self.0);
                 }
             }
-            new_list.block_lengths[block_idx] = elems;
         }
 
-        new_list
+        let dropped = RefCell::new(Vec::new());
+        let len = BLOCK_STARTS[3];
+        let mut list: SegmentedList<_> = (0..len).map(|i| PanicOnDrop(i, &dropped)).collect();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            drop(list.drain(2..BLOCK_STARTS[2]));
+        }));
+        assert!(result.is_err());
+        // elements after the panicking one are leaked, the tail still closes the gap
+        assert_eq!(*dropped.borrow(), (2..=START_SIZE + 1).collect::<Vec<_>>());
+        let remaining: Vec<_> = (0..2).chain(BLOCK_STARTS[2]..len).collect();
+        assert_eq!(list.iter().map(|v| v.0).collect::<Vec<_>>(), remaining);
+
+        list.push(PanicOnDrop(len, &dropped));
+        dropped.borrow_mut().clear();
+        drop(list);
+        let mut expected = remaining;
+        expected.push(len);
+        assert_eq!(*dropped.borrow(), expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "range end index 11 out of range for slice of length 10")]
+    fn drain_panics_past_len() {
+        let mut list: SegmentedList<_> = (0..10).collect();
+        list.drain(5..11);
+    }
+
+    #[test]
+    #[should_panic(expected = "slice index starts at 6 but ends at 5")]
+    fn drain_panics_on_inverted_range() {
+        let mut list: SegmentedList<_> = (0..10).collect();
+        #[allow(clippy::reversed_empty_ranges)]
+        list.drain(6..5);
+    }
+
+    #[test]
+    fn into_vec_and_back() {
+        fn takes_vec(vec: Vec<String>) -> usize {
+            vec.len()
+        }
+
+        let source: Vec<String> = (0..(BLOCK_STARTS[2] + 1)).map(|i| i.to_string()).collect();
+        let list: SegmentedList<String> = source.clone().into();
+        assert_eq!(list, source);
+        let back: Vec<String> = list.into();
+        assert_eq!(back, source);
+
+        let list: SegmentedList<String> = source[..3].into();
+        assert_eq!(takes_vec(list.into()), 3);
+    }
+
+    #[test]
+    fn sort_reverse_filled_list() {
+        let len = BLOCK_STARTS[4] + 3;
+        let mut list: SegmentedList<_> = (0..len).rev().collect();
+        list.sort_unstable();
+        assert!(list.iter().copied().eq(0..len));
+
+        let mut list: SegmentedList<_> = (0..len).rev().map(|i| i.to_string()).collect();
+        let mut vec: Vec<_> = list.iter().cloned().collect();
+        list.sort();
+        vec.sort();
+        assert_eq!(list, vec);
+        // sorting keeps the list usable
+        list.push("end".into());
+        assert_eq!(list.len(), len + 1);
+
+        for len in [0, 1, 2, START_SIZE, START_SIZE + 1] {
+            let mut list: SegmentedList<_> = (0..len).map(|i| (i * 7) % 5).collect();
+            let mut vec = list.iter().copied().collect::<Vec<_>>();
+            vec.sort();
+            let mut stable = list.clone();
+            stable.sort();
+            list.sort_unstable();
+            assert_eq!(list, vec);
+            assert_eq!(stable, vec);
+        }
+    }
+
+    #[test]
+    fn sort_panicking_compare_keeps_elements() {
+        struct Panicky(usize);
+        impl PartialEq for Panicky {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+        impl Eq for Panicky {}
+        impl PartialOrd for Panicky {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        impl Ord for Panicky {
+            fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+                assert!(self.0 != 13 && other.0 != 13, "comparing 13");
+                self.0.cmp(&other.0)
+            }
+        }
+
+        let len = START_SIZE * 5;
+        let mut list: SegmentedList<_> = (0..len).rev().map(Panicky).collect();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| list.sort()));
+        assert!(result.is_err());
+        let mut ids: Vec<_> = list.iter().map(|v| v.0).collect();
+        ids.sort_unstable();
+        assert_eq!(ids, (0..len).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn remove_at_block_boundaries() {
+        let len = BLOCK_STARTS[3] + 2;
+        let mut list: SegmentedList<_> = (0..len).collect();
+        let mut vec: Vec<_> = (0..len).collect();
+        for idx in [
+            BLOCK_STARTS[3] - 1,
+            BLOCK_STARTS[2],
+            START_SIZE,
+            START_SIZE - 1,
+            0,
+        ] {
+            assert_eq!(list.remove(idx), vec.remove(idx));
+            assert_eq!(list, vec);
+        }
+
+        // the slots freed at the end are reused
+        list.push(1000);
+        vec.push(1000);
+        assert_eq!(list, vec);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_binary_round_trips() {
+        for len in [0, 1, BLOCK_STARTS[3] + 7] {
+            let list: SegmentedList<(u32, String)> =
+                (0..len).map(|i| (i as u32, format!("{}", i))).collect();
+
+            let bytes = bincode::serialize(&list).unwrap();
+            let back: SegmentedList<(u32, String)> = bincode::deserialize(&bytes).unwrap();
+            assert_eq!(back, list);
+
+            let bytes = rmp_serde::to_vec(&list).unwrap();
+            let back: SegmentedList<(u32, String)> = rmp_serde::from_slice(&bytes).unwrap();
+            assert_eq!(back, list);
+        }
+    }
+
+    #[test]
+    fn reverse_across_blocks() {
+        for len in [0, 1, START_SIZE * 3, START_SIZE * 3 + 1] {
+            let mut list: SegmentedList<_> = (0..len).map(|i| i.to_string()).collect();
+            list.reverse();
+            let expected: Vec<_> = (0..len).rev().map(|i| i.to_string()).collect();
+            assert_eq!(list, expected);
+        }
+    }
+
+    #[test]
+    fn swap_remove_drops_each_element_once() {
+        let dropped = RefCell::new(Vec::new());
+        let len = BLOCK_STARTS[2] + 1;
+        let mut list: SegmentedList<_> = (0..len).map(|i| DropCounter(i, &dropped)).collect();
+
+        // the last element lives in block 2, idx in block 0
+        let removed = list.swap_remove(1);
+        assert_eq!(removed.0, 1);
+        assert_eq!(list[1].0, len - 1);
+        assert!(dropped.borrow().is_empty());
+        drop(removed);
+        assert_eq!(*dropped.borrow(), [1]);
+
+        // removing the last element is a plain pop
+        let last = list.len() - 1;
+        assert_eq!(list.swap_remove(last).0, last);
+        assert_eq!(list.len(), len - 2);
+
+        drop(list);
+        let mut dropped = dropped.into_inner();
+        dropped.sort_unstable();
+        assert_eq!(dropped, (0..len).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn write_appends_bytes() {
+        use std::io::Write;
+
+        let mut list: SegmentedList<u8> = SegmentedList::new();
+        write!(list, "hello {}", 42).unwrap();
+        assert_eq!(list, *b"hello 42");
+
+        let payload: Vec<u8> = (0..=255).cycle().take(BLOCK_STARTS[5] + 3).collect();
+        {
+            let mut writer = std::io::BufWriter::with_capacity(7, &mut list);
+            writer.write_all(&payload).unwrap();
+            writer.flush().unwrap();
+        }
+        assert_eq!(list.len(), 8 + payload.len());
+        assert!(list.iter().skip(8).eq(payload.iter()));
+    }
+
+    #[test]
+    fn swap_non_clone_elements_between_blocks() {
+        // neither Copy nor Clone
+        struct Token(usize);
+
+        let mut list: SegmentedList<_> = (0..(START_SIZE * 2)).map(Token).collect();
+        list.swap(2, START_SIZE + 4);
+        assert_eq!(list.get(2).map(|t| t.0), Some(START_SIZE + 4));
+        assert_eq!(list.get(START_SIZE + 4).map(|t| t.0), Some(2));
+        assert!(
+            list.iter()
+                .enumerate()
+                .filter(|&(i, _)| i != 2 && i != START_SIZE + 4)
+                .all(|(i, t)| t.0 == i)
+        );
+    }
+
+    #[test]
+    fn insert_into_full_blocks_spills_into_next() {
+        for full in [START_SIZE, BLOCK_STARTS[2], BLOCK_STARTS[3]] {
+            let mut list: SegmentedList<_> = (0..full).collect();
+            let mut expected: Vec<_> = (0..full).collect();
+            let blocks = list.allocated_blocks();
+
+            // every block is full, the last element moves into a block that isn't allocated yet
+            list.insert(0, 1000);
+            expected.insert(0, 1000);
+            assert_eq!(list.allocated_blocks(), blocks + 1);
+            assert_eq!(list, expected);
+
+            list.insert(START_SIZE, 2000);
+            expected.insert(START_SIZE, 2000);
+            let len = list.len();
+            list.insert(len, 3000);
+            expected.insert(len, 3000);
+            assert_eq!(list, expected);
+            assert_eq!(list.last(), Some(&3000));
+        }
+    }
+
+    #[test]
+    fn read_crosses_block_boundaries() {
+        use std::io::{Read, Write};
+
+        let mut list: SegmentedList<u8> = SegmentedList::new();
+        let mut buf = [0u8; 4];
+        assert_eq!(list.reader().read(&mut buf).unwrap(), 0);
+
+        let payload: Vec<u8> = (0..BLOCK_STARTS[4] + 3).map(|i| i as u8).collect();
+        list.write_all(&payload).unwrap();
+
+        let mut reader = list.reader();
+        let mut first = [0u8; START_SIZE + 3];
+        reader.read_exact(&mut first).unwrap();
+        assert_eq!(first, payload[..START_SIZE + 3]);
+        assert_eq!(reader.len(), payload.len() - first.len());
+
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, payload[START_SIZE + 3..]);
+        assert_eq!(reader.read(&mut buf).unwrap(), 0);
+        assert_eq!(list.len(), payload.len());
+
+        // a view stops at its end, also inside a block
+        let mut reader = list.slice(START_SIZE - 2..START_SIZE + 1);
+        assert_eq!(reader.read(&mut buf).unwrap(), 3);
+        assert_eq!(buf[..3], payload[START_SIZE - 2..START_SIZE + 1]);
+    }
+
+    #[test]
+    fn read_after_truncate_and_push() {
+        use std::io::{Read, Write};
+
+        let mut list: SegmentedList<u8> = SegmentedList::new();
+        list.write_all(b"hello segmented world").unwrap();
+        let mut buf = [0u8; 5];
+        list.slice(..).read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"hello");
+
+        // every reader starts at its own view, shrinking and regrowing can't skip or reread
+        list.truncate(2);
+        list.write_all(b"y there").unwrap();
+        let mut all = String::new();
+        list.reader().read_to_string(&mut all).unwrap();
+        assert_eq!(all, "hey there");
+    }
+
+    #[test]
+    fn get_disjoint_mut_across_blocks() {
+        let mut list: SegmentedList<_> = (0..(START_SIZE * 3)).map(|i| i.to_string()).collect();
+        let [a, b] = list.get_disjoint_mut([0, START_SIZE + 1]).unwrap();
+        std::mem::swap(a, b);
+        a.push('!');
+        assert_eq!(list[0], format!("{}!", START_SIZE + 1));
+        assert_eq!(list[START_SIZE + 1], "0");
+
+        assert!(list.get_disjoint_mut([1, 2, 1]).is_none());
+        assert!(list.get_disjoint_mut([1, START_SIZE * 3]).is_none());
+        assert_eq!(list.get_disjoint_mut([]), Some([]));
+    }
+
+    #[test]
+    fn slice_views_ranges_across_blocks() {
+        let len = BLOCK_STARTS[3] + 4;
+        let list: SegmentedList<_> = (0..len).collect();
+        let vec: Vec<_> = (0..len).collect();
+
+        let view = list.slice(3..BLOCK_STARTS[2] + 2);
+        assert_eq!(view.len(), BLOCK_STARTS[2] - 1);
+        assert_eq!(view[0], 3);
+        assert_eq!(view.get(view.len()), None);
+        assert!(view.iter().eq(vec[3..BLOCK_STARTS[2] + 2].iter()));
+        assert!(
+            view.iter()
+                .rev()
+                .eq(vec[3..BLOCK_STARTS[2] + 2].iter().rev())
+        );
+
+        let mut sum = 0;
+        for v in list.slice(3..7) {
+            sum += v;
+        }
+        assert_eq!(sum, 3 + 4 + 5 + 6);
+
+        assert!(list.slice(..).iter().eq(vec.iter()));
+        assert!(list.slice(START_SIZE..).iter().eq(vec[START_SIZE..].iter()));
+        assert!(
+            list.slice(..=START_SIZE)
+                .iter()
+                .eq(vec[..=START_SIZE].iter())
+        );
+        assert!(list.slice(len..).is_empty());
+        assert_eq!(list.slice(len..).iter().next(), None);
+
+        // views of views stay relative to their own start
+        let inner = view.slice(START_SIZE..START_SIZE + 2);
+        assert_eq!(format!("{:?}", inner), format!("{:?}", &vec[11..13]));
+        assert_eq!(inner.first(), Some(&11));
+        assert_eq!(inner.last(), Some(&12));
+    }
+
+    #[test]
+    #[should_panic(expected = "index 4 out of bounds for SegmentedSlice of length 4")]
+    fn slice_index_panics_past_its_len() {
+        let list: SegmentedList<_> = (0..20).collect();
+        let _ = list.slice(2..6)[4];
+    }
+
+    #[test]
+    #[should_panic(expected = "range end index 21 out of range for slice of length 20")]
+    fn slice_panics_past_len() {
+        let list: SegmentedList<_> = (0..20).collect();
+        list.slice(2..21);
+    }
+
+    #[test]
+    fn sort_by_random_values() {
+        // xorshift, deterministic without pulling in a rng crate
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % 1000
+        };
+        let vec: Vec<u64> = (0..BLOCK_STARTS[6] + 11).map(|_| next()).collect();
+
+        let mut list = SegmentedList::from(&vec[..]);
+        list.sort_unstable_by(|a, b| b.cmp(a));
+        let mut expected = vec.clone();
+        expected.sort_by(|a, b| b.cmp(a));
+        assert_eq!(list, expected);
+
+        let mut list = SegmentedList::from(&vec[..]);
+        list.sort_by(|a, b| a.cmp(b));
+        expected.reverse();
+        assert_eq!(list, expected);
+    }
+
+    #[test]
+    fn sort_by_is_stable() {
+        let len = BLOCK_STARTS[4] + 1;
+        // (key, original index), many elements share a key
+        let mut list: SegmentedList<_> = (0..len).map(|i| ((i * 7) % 5, i)).collect();
+        list.sort_by(|a, b| a.0.cmp(&b.0));
+        assert!(
+            list.iter()
+                .zip(list.iter().skip(1))
+                .all(|(a, b)| a.0 < b.0 || (a.0 == b.0 && a.1 < b.1))
+        );
+        assert_eq!(list.len(), len);
+    }
+
+    #[test]
+    fn segments_cover_initialized_elements() {
+        let len = BLOCK_STARTS[3] + 5;
+        let list: SegmentedList<_> = (0..len).collect();
+        let lens: Vec<_> = list.segments().map(<[_]>::len).collect();
+        assert_eq!(lens, [START_SIZE, START_SIZE * 2, START_SIZE * 4, 5]);
+
+        let concatenated: Vec<_> = list.segments().flatten().copied().collect();
+        assert_eq!(concatenated, list.clone().to_vec());
+
+        let mut buf = vec![0; len];
+        let mut offset = 0;
+        for segment in list.segments() {
+            buf[offset..offset + segment.len()].copy_from_slice(segment);
+            offset += segment.len();
+        }
+        assert_eq!(list, buf);
+    }
+
+    #[test]
+    fn as_flat_slices_match_segments() {
+        let list: SegmentedList<_> = (0..BLOCK_STARTS[5] + 3).collect();
+        assert_eq!(list.as_flat_slices().count(), 6);
+        assert!(list.as_flat_slices().eq(list.segments()));
+        assert!(list.as_flat_slices().flatten().eq(list.iter()));
+    }
+
+    #[test]
+    fn segments_last_slice_ends_at_len() {
+        for len in [
+            1,
+            START_SIZE,
+            START_SIZE + 1,
+            BLOCK_STARTS[4] - 1,
+            BLOCK_STARTS[4],
+        ] {
+            let list: SegmentedList<_> = (0..len).collect();
+            let last = list.segments().last().unwrap();
+            let SegmentedIdx(block, _) = list.idx_to_block_idx(len - 1);
+            assert_eq!(last.len(), len - BLOCK_STARTS[block]);
+            assert_eq!(last.last(), Some(&(len - 1)));
+            assert_eq!(list.segments().map(<[_]>::len).sum::<usize>(), len);
+        }
+    }
+
+    #[test]
+    fn segments_of_empty_list_yield_nothing() {
+        let empty: SegmentedList<u32> = SegmentedList::new();
+        assert_eq!(empty.segments().count(), 0);
+        let reserved: SegmentedList<u32> = SegmentedList::with_capacity(BLOCK_STARTS[4]);
+        assert_eq!(reserved.segments().count(), 0);
+
+        let mut list: SegmentedList<_> = (0..BLOCK_STARTS[2] + 1).collect();
+        list.truncate(BLOCK_STARTS[2]);
+        assert_eq!(list.segments().count(), 2);
+        list.clear();
+        assert_eq!(list.segments().count(), 0);
     }
-}
 
-impl<T> Extend<T> for SegmentedList<T> {
-    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
-        for item in iter {
-            self.push(item);
-        }
+    #[test]
+    fn as_segments_of_empty_list_yield_nothing() {
+        let mut list: SegmentedList<u32> = SegmentedList::with_capacity(BLOCK_STARTS[3]);
+        assert_eq!(list.as_segments().next(), None);
+
+        list.extend(0..BLOCK_STARTS[2] as u32 + 1);
+        assert_eq!(
+            list.as_segments().map(<[_]>::len).sum::<usize>(),
+            list.len()
+        );
+        assert!(
+            list.as_segments()
+                .flatten()
+                .eq(list.clone().to_vec().iter())
+        );
+        list.clear();
+        assert_eq!(list.as_segments().next(), None);
     }
-}
 
-impl<T> std::iter::FromIterator<T> for SegmentedList<T> {
-    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
-        let mut sl = SegmentedList::new();
-        sl.extend(iter);
-        sl
+    #[test]
+    fn as_segments_mut_cover_initialized_elements() {
+        let len = BLOCK_STARTS[3] + 5;
+        let mut list: SegmentedList<_> = (0..len).collect();
+        for segment in list.as_segments_mut() {
+            segment.iter_mut().for_each(|v| *v *= 2);
+        }
+        assert!((0..len).all(|i| list[i] == i * 2));
+
+        // per block routines on slices held at the same time
+        let mut segments: Vec<_> = list.as_segments_mut().collect();
+        assert_eq!(segments.len(), 4);
+        assert_eq!(segments[3].len(), 5);
+        segments[0].fill(0);
+        segments[1].reverse();
+        assert_eq!(list[0], 0);
+        assert_eq!(list[START_SIZE], (BLOCK_STARTS[2] - 1) * 2);
+
+        let mut empty: SegmentedList<u8> = SegmentedList::with_capacity(100);
+        assert_eq!(empty.as_segments_mut().count(), 0);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::alloc;
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_u64_round_trip_preallocates() {
+        let list: SegmentedList<u64> = (0..(BLOCK_STARTS[5] as u64 + 1)).map(|i| i * i).collect();
 
-    use super::*;
-    use std::{cell::RefCell, rc::Rc};
+        let json = serde_json::to_string(&list).unwrap();
+        let back: SegmentedList<u64> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, list);
 
-    #[global_allocator]
-    static A: alloc::SegmentedAlloc = alloc::SegmentedAlloc::new();
+        // bincode knows the length up front, so exactly the needed blocks are allocated
+        let bytes = bincode::serialize(&list).unwrap();
+        let back: SegmentedList<u64> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(back, list);
+        assert_eq!(back.allocated_blocks(), 6);
+        assert_eq!(
+            back.capacity(),
+            SegmentedList::<u64>::with_capacity(list.len()).capacity()
+        );
+    }
 
     #[test]
-    fn push_and_get_basic() {
-        let mut list = SegmentedList::new();
+    fn sort_by_key_variants() {
+        let len = BLOCK_STARTS[4] + 9;
+        let names: Vec<String> = (0..len)
+            .map(|i| format!("{:x}", (i * 7919) % 4099))
+            .collect();
+        let mut expected = names.clone();
+        expected.sort_by_key(|name| name.len());
 
-        list.push(42);
-        list.push(100);
-        list.push(7);
+        let mut list = SegmentedList::from(&names[..]);
+        list.sort_by_key(|name| name.len());
+        assert_eq!(list, expected);
 
-        assert_eq!(list.len, 3);
-        assert_eq!(list.get(0), Some(&42));
-        assert_eq!(list.get(1), Some(&100));
-        assert_eq!(list.get(2), Some(&7));
-        assert_eq!(list.get(3), None); // out of bounds
+        let calls = RefCell::new(0);
+        let mut list = SegmentedList::from(&names[..]);
+        list.sort_by_cached_key(|name| {
+            *calls.borrow_mut() += 1;
+            name.len()
+        });
+        // stable like sort_by_key, with a single key computation per element
+        assert_eq!(list, expected);
+        assert_eq!(*calls.borrow(), len);
+
+        // unstable, thus only the keys are compared
+        let mut list = SegmentedList::from(&names[..]);
+        list.sort_unstable_by_key(|name| name.len());
+        assert!(
+            list.iter()
+                .map(String::len)
+                .eq(expected.iter().map(String::len))
+        );
+        let mut sorted = list.to_vec();
+        sorted.sort();
+        let mut names = names;
+        names.sort();
+        assert_eq!(sorted, names);
     }
 
     #[test]
-    fn push_and_get_mut() {
+    fn push_slice_spans_many_blocks() {
+        #[derive(Clone, Copy, PartialEq, Debug)]
+        struct Pixel(u8, u8, u8);
+
+        let pixels: Vec<_> = (0..(BLOCK_STARTS[7] + 3))
+            .map(|i| Pixel(i as u8, (i >> 8) as u8, 7))
+            .collect();
         let mut list = SegmentedList::new();
+        list.push(Pixel(0, 0, 0));
+        // the first call stops right before the end of block 2
+        list.push_slice(&pixels[..BLOCK_STARTS[3] - 2]);
+        list.push_slice(&pixels[BLOCK_STARTS[3] - 2..]);
+        assert_eq!(list.len(), pixels.len() + 1);
+        assert_eq!(list[0], Pixel(0, 0, 0));
+        assert!(list.iter().skip(1).eq(pixels.iter()));
+        assert_eq!(list.allocated_blocks(), 8);
+    }
 
-        list.push(42);
-        list.push(100);
-        list.push(7);
+    #[test]
+    fn push_slice_block_boundaries() {
+        // ends exactly at the end of block 1, the next push starts block 2
+        let mut list = SegmentedList::new();
+        list.push_slice(&(0..BLOCK_STARTS[2] as u64).collect::<Vec<_>>());
+        assert_eq!(list.len(), BLOCK_STARTS[2]);
+        assert_eq!(list.allocated_blocks(), 2);
+        assert_eq!(list.segments().last().map(<[_]>::len), Some(START_SIZE * 2));
+        list.push(BLOCK_STARTS[2] as u64);
+        assert!(list.iter().copied().eq(0..BLOCK_STARTS[2] as u64 + 1));
 
-        assert_eq!(list.len, 3);
-        assert_eq!(list.get(0), Some(&42));
-        assert_eq!(list.get(1), Some(&100));
-        assert_eq!(list.get(2), Some(&7));
-        assert_eq!(list.get(3), None);
+        // starts inside block 0 and spans three blocks
+        let mut list: SegmentedList<u64> = [7, 7, 7].into_iter().collect();
+        let source: Vec<u64> = (0..(BLOCK_STARTS[2] + 4) as u64).collect();
+        list.push_slice(&source);
+        assert_eq!(list.segments().count(), 3);
+        assert!(list.iter().skip(3).eq(source.iter()));
+
+        let mut copied: SegmentedList<u64> = [7, 7, 7].into_iter().collect();
+        copied.extend_from_slice(&source);
+        assert_eq!(copied, list);
+        let mut cloned: SegmentedList<u64> = [7, 7, 7].into_iter().collect();
+        cloned.extend_from_slice_clone(&source);
+        assert_eq!(cloned, list);
     }
 
     #[test]
-    fn into_vec_flattens_correctly() {
-        let mut list = SegmentedList::new();
+    fn from_vec_round_trips_ten_thousand() {
+        let vec: Vec<u64> = (0..10_000).map(|i| i * 3).collect();
+        let list = SegmentedList::from(vec.clone());
+        assert_eq!(
+            list.capacity(),
+            SegmentedList::<u64>::with_capacity(10_000).capacity()
+        );
+        assert_eq!(list.to_vec(), vec);
 
-        for i in 0..20 {
-            list.push(i);
+        let strings: Vec<String> = (0..10_000).map(|i| i.to_string()).collect();
+        let list: SegmentedList<_> = strings.clone().into();
+        assert_eq!(list.to_vec(), strings);
+    }
+
+    #[test]
+    fn binary_search_family_edges() {
+        let empty: SegmentedList<(u32, &str)> = SegmentedList::new();
+        assert_eq!(empty.binary_search_by(|v| v.0.cmp(&1)), Err(0));
+        assert_eq!(empty.binary_search_by_key(&1, |v| v.0), Err(0));
+
+        // keys 10, 20, 30, ... with the key at a block boundary being BLOCK_STARTS[2] * 10 + 10
+        let len = BLOCK_STARTS[3] as u32;
+        let list: SegmentedList<_> = (1..=len).map(|i| (i * 10, "entry")).collect();
+        let last = len as usize - 1;
+        for (key, expected) in [
+            (10, Ok(0)),
+            (5, Err(0)),
+            (len * 10, Ok(last)),
+            (len * 10 + 1, Err(last + 1)),
+            (BLOCK_STARTS[2] as u32 * 10 + 10, Ok(BLOCK_STARTS[2])),
+            (BLOCK_STARTS[2] as u32 * 10 + 5, Err(BLOCK_STARTS[2])),
+        ] {
+            assert_eq!(list.binary_search_by_key(&key, |v| v.0), expected);
+            assert_eq!(list.binary_search_by(|v| v.0.cmp(&key)), expected);
         }
+    }
 
-        let vec = list.to_vec();
-        assert_eq!(vec.len(), 20);
-        assert_eq!(vec, (0..20).collect::<Vec<_>>());
+    #[test]
+    fn segmented_idx_lookups() {
+        let mut list: SegmentedList<_> = (0..(START_SIZE * 3)).collect();
+        let idx = list.compute_segmented_idx(START_SIZE + 2).unwrap();
+        assert_eq!(
+            unsafe { list.get_with_segmented_idx(idx) },
+            Some(&(START_SIZE + 2))
+        );
+        *unsafe { list.get_mut_with_segmented_idx(idx) }.unwrap() = 1000;
+        assert_eq!(list[START_SIZE + 2], 1000);
+        assert_eq!(list.get_checked_with_segmented_idx(idx), Some(&1000));
+        *list.get_checked_mut_with_segmented_idx(idx).unwrap() = 1001;
+        assert_eq!(list[START_SIZE + 2], 1001);
+        assert_eq!(list.compute_segmented_idx(START_SIZE * 3), None);
+
+        // a cached idx past the end of a shrunk list is rejected
+        list.truncate(START_SIZE);
+        assert_eq!(list.get_checked_with_segmented_idx(idx), None);
+        assert_eq!(list.get_checked_mut_with_segmented_idx(idx), None);
+
+        // also if the block the idx points into was released
+        let kept = list.compute_segmented_idx(START_SIZE - 1).unwrap();
+        list.extend(START_SIZE..BLOCK_STARTS[4] + 1);
+        let released = list.compute_segmented_idx(BLOCK_STARTS[4]).unwrap();
+        list.truncate(START_SIZE);
+        list.shrink_to_fit();
+        assert_eq!(list.get_checked_with_segmented_idx(released), None);
+        assert_eq!(
+            list.get_checked_with_segmented_idx(kept),
+            Some(&(START_SIZE - 1))
+        );
+        list.extend(START_SIZE..BLOCK_STARTS[4] + 1);
+        assert_eq!(
+            list.get_checked_with_segmented_idx(released),
+            Some(&BLOCK_STARTS[4])
+        );
     }
 
     #[test]
-    fn index_trait_returns_correct_values() {
-        let mut list = SegmentedList::new();
-        for i in 0..10 {
-            list.push(i * 2);
-        }
-        assert_eq!(list[0], 0);
-        assert_eq!(list[1], 2);
-        assert_eq!(list[9], 18);
+    fn extend_from_slice_clone_clones() {
+        let source: Vec<String> = (0..(BLOCK_STARTS[2] + 1)).map(|i| i.to_string()).collect();
+        let mut list: SegmentedList<String> = ["first".to_string()].into_iter().collect();
+        list.extend_from_slice_clone(&source);
+        assert_eq!(list.len(), source.len() + 1);
+        assert!(list.iter().skip(1).eq(source.iter()));
+        assert_eq!(list[0], "first");
     }
 
     #[test]
-    fn index_mut_trait_returns_correct_values() {
-        let mut list = SegmentedList::new();
-        for i in 0..10 {
-            list.push(i * 2);
+    fn copy_from_slice_overwrites_prefix() {
+        let len = BLOCK_STARTS[3] + 2;
+        let mut list: SegmentedList<u32> = (0..len as u32).collect();
+        let src: Vec<u32> = (0..(BLOCK_STARTS[2] as u32 + 3))
+            .map(|i| i + 1000)
+            .collect();
+        list.copy_from_slice(&src);
+        let expected: Vec<u32> = src
+            .iter()
+            .copied()
+            .chain(src.len() as u32..len as u32)
+            .collect();
+        assert_eq!(list, expected);
+
+        list.copy_from_slice(&[]);
+        assert_eq!(list, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "source slice length (11) exceeds list length (10)")]
+    fn copy_from_slice_panics_on_longer_source() {
+        let mut list: SegmentedList<u8> = (0..10).collect();
+        list.copy_from_slice(&[0; 11]);
+    }
+
+    #[test]
+    // the allocator's UnsafeCell doesn't take part in hashing or equality
+    #[allow(clippy::mutable_key_type)]
+    fn list_as_hashmap_key() {
+        let mut map = std::collections::HashMap::new();
+        let key: SegmentedList<_> = (0..(BLOCK_STARTS[2] + 3)).map(|i| i.to_string()).collect();
+        map.insert(key, "value");
+
+        let mut lookup = SegmentedList::with_capacity(1000);
+        for i in 0..(BLOCK_STARTS[2] + 3) {
+            lookup.push(i.to_string());
         }
+        assert_eq!(map.get(&lookup), Some(&"value"));
 
-        assert_eq!(list[0], 0);
-        assert_eq!(list[1], 2);
-        assert_eq!(list[9], 18);
+        lookup.pop();
+        assert_eq!(map.get(&lookup), None);
+    }
 
-        list[0] = 5;
-        list[1] = 3;
-        list[9] = 36;
+    #[test]
+    fn partition_point_edges() {
+        let empty: SegmentedList<u64> = SegmentedList::new();
+        assert_eq!(empty.partition_point(|_| true), 0);
 
-        assert_eq!(list[0], 5);
-        assert_eq!(list[1], 3);
-        assert_eq!(list[9], 36);
+        let list: SegmentedList<_> = (0..BLOCK_STARTS[3]).collect();
+        assert_eq!(list.partition_point(|_| true), list.len());
+        assert_eq!(list.partition_point(|_| false), 0);
+        // split exactly at the start of the second block
+        assert_eq!(list.partition_point(|&v| v < START_SIZE), START_SIZE);
+        for split in 0..=list.len() {
+            assert_eq!(list.partition_point(|&v| v < split), split);
+        }
     }
 
     #[test]
-    #[should_panic(expected = "out of bounds")]
-    fn index_panics_on_invalid() {
-        let mut list = SegmentedList::new();
-        list.push(1);
-        let _ = list[1]; // index 1 invalid (len = 1)
+    fn eq_against_slices_and_vecs() {
+        let len = BLOCK_STARTS[2] + 3;
+        let list: SegmentedList<_> = (0..len).collect();
+        let vec: Vec<_> = (0..len).collect();
+
+        assert!(list == vec[..]);
+        assert!(vec[..] == list);
+        assert_eq!(list, vec);
+        assert_eq!(vec, list);
+        assert_eq!(list, &vec[..]);
+        assert_eq!(&vec[..], list);
+
+        let shorter = &vec[..len - 1];
+        assert!(list != *shorter);
+        assert!(*shorter != list);
+        assert_ne!(list, shorter.to_vec());
+        assert_ne!(shorter.to_vec(), list);
+
+        let mut differs = vec.clone();
+        differs[BLOCK_STARTS[2] + 1] = 0;
+        assert!(list != differs[..]);
+        assert_ne!(list, differs);
+
+        let small: SegmentedList<_> = [1, 2, 3].into_iter().collect();
+        assert!(small == [1, 2, 3][..]);
+        assert_eq!(small, vec![1, 2, 3]);
+        assert_ne!(small, vec![1, 2]);
     }
 
     #[test]
-    fn works_across_blocks() {
-        let mut list = SegmentedList::new();
+    fn is_sorted_checks_pairs_across_blocks() {
+        let empty: SegmentedList<u32> = SegmentedList::new();
+        assert!(empty.is_sorted());
+        let single: SegmentedList<_> = [7].into_iter().collect();
+        assert!(single.is_sorted());
 
-        // Fill more than START_SIZE to force allocation of next block(s)
-        for i in 0..(START_SIZE + 5) {
-            list.push(i);
+        let mut list: SegmentedList<_> = (0..BLOCK_STARTS[3]).collect();
+        assert!(list.is_sorted());
+        assert!(!list.is_sorted_by(|a, b| a > b));
+
+        // every block on its own is still sorted, only the pair spanning blocks 1 and 2 is not
+        list[BLOCK_STARTS[2] - 1] = BLOCK_STARTS[2] + 1;
+        assert!(list.segments().all(|elements| elements.is_sorted()));
+        assert!(!list.is_sorted());
+        assert!(list.is_sorted_by(|a, b| a.abs_diff(*b) <= 3));
+
+        let floats: SegmentedList<_> = [1.0, f64::NAN, 2.0].into_iter().collect();
+        assert!(!floats.is_sorted());
+    }
+
+    #[test]
+    fn splice_matches_vec() {
+        let len = BLOCK_STARTS[3] + 5;
+        for (range, replacement) in [
+            (0..0, 0..0),
+            (3..START_SIZE + 2, 100..103),
+            (3..START_SIZE + 2, 100..100 + START_SIZE - 1),
+            (
+                START_SIZE - 1..BLOCK_STARTS[2] + 1,
+                100..100 + BLOCK_STARTS[3],
+            ),
+            (0..len, 100..110),
+            (len..len, 100..100 + BLOCK_STARTS[2]),
+            (5..BLOCK_STARTS[3], 100..100),
+        ] {
+            let mut list: SegmentedList<_> = (0..len).map(|i| i.to_string()).collect();
+            let mut vec: Vec<_> = (0..len).map(|i| i.to_string()).collect();
+            let removed: Vec<_> = list
+                .splice(range.clone(), replacement.clone().map(|i| i.to_string()))
+                .collect();
+            let expected: Vec<_> = vec
+                .splice(range, replacement.map(|i| i.to_string()))
+                .collect();
+            assert_eq!(removed, expected);
+            assert_eq!(list, vec);
+            list.push("end".into());
+            assert_eq!(list.last().map(String::as_str), Some("end"));
         }
+    }
 
-        let vec = list.to_vec();
-        assert_eq!(vec, (0..(START_SIZE + 5)).collect::<Vec<_>>());
+    #[test]
+    fn splice_drops_unconsumed_and_survives_a_panicking_replacement() {
+        let mut list: SegmentedList<_> = (0..BLOCK_STARTS[2]).map(|i| i.to_string()).collect();
+        let mut splice = list.splice(2..START_SIZE + 4, ["a".to_string(), "b".into()]);
+        assert_eq!(splice.len(), START_SIZE + 2);
+        assert_eq!(splice.next_back().as_deref(), Some("11"));
+        drop(splice);
+        let expected: Vec<String> = ["0", "1", "a", "b"]
+            .into_iter()
+            .map(String::from)
+            .chain((START_SIZE + 4..BLOCK_STARTS[2]).map(|i| i.to_string()))
+            .collect();
+        assert_eq!(list, expected);
+
+        // panics once the gap is filled, the elements written so far stay in front of the tail
+        let mut list: SegmentedList<_> = (0..BLOCK_STARTS[2]).collect();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            drop(list.splice(
+                2..4,
+                (100..).map(|i| {
+                    if i < 103 {
+                        i
+                    } else {
+                        panic!("replacement {i}")
+                    }
+                }),
+            ));
+        }));
+        assert!(result.is_err());
+        let expected: Vec<_> = [0, 1, 100, 101]
+            .into_iter()
+            .chain(4..BLOCK_STARTS[2])
+            .collect();
+        assert_eq!(list, expected);
     }
 
     #[test]
-    fn empty_list_into_vec() {
-        let list: SegmentedList<i32> = SegmentedList::new();
-        let vec = list.to_vec();
-        assert!(vec.is_empty());
+    fn shrink_to_fit_releases_trailing_blocks() {
+        let mut list: SegmentedList<_> = (0..BLOCK_STARTS[10]).map(|i| i.to_string()).collect();
+        assert_eq!(list.capacity(), BLOCK_STARTS[10]);
+        list.truncate(START_SIZE + 1);
+        assert_eq!(list.capacity(), BLOCK_STARTS[10]);
+
+        list.shrink_to_fit();
+        assert_eq!(list.allocated_blocks(), 2);
+        assert_eq!(list.capacity(), BLOCK_STARTS[2]);
+        assert_eq!(
+            list,
+            (0..=START_SIZE).map(|i| i.to_string()).collect::<Vec<_>>()
+        );
+
+        // the released blocks are allocated again on demand
+        list.extend((START_SIZE + 1..BLOCK_STARTS[6] + 3).map(|i| i.to_string()));
+        assert_eq!(list.capacity(), BLOCK_STARTS[7]);
+        assert!(list.iter().enumerate().all(|(i, v)| *v == i.to_string()));
+
+        list.clear();
+        list.shrink_to_fit();
+        assert_eq!(list.capacity(), START_SIZE);
+        list.shrink_to_fit();
+        assert_eq!(list.capacity(), START_SIZE);
+        list.push("again".into());
+        assert_eq!(list, ["again"]);
+
+        let mut full_blocks: SegmentedList<_> = (0..BLOCK_STARTS[3]).collect();
+        full_blocks.reserve(1);
+        full_blocks.shrink_to_fit();
+        assert_eq!(full_blocks.capacity(), BLOCK_STARTS[3]);
     }
 
     #[test]
-    fn exact_block_boundaries() {
-        let mut list = SegmentedList::new();
-        let blocks_to_test = 3;
-        let mut total = 0;
-        for block_idx in 0..blocks_to_test {
-            let size = START_SIZE << block_idx;
-            for i in 0..size {
-                list.push(total + i);
-            }
-            total += size;
-            assert_eq!(list.len, total);
-        }
-        let vec = list.to_vec();
-        assert_eq!(vec, (0..total).collect::<Vec<_>>());
+    fn position_and_rposition_walk_the_blocks() {
+        let empty: SegmentedList<u8> = SegmentedList::new();
+        assert_eq!(empty.position(|_| true), None);
+        assert_eq!(empty.rposition(|_| true), None);
+
+        // the last block is only partially filled
+        let len = BLOCK_STARTS[3] + 3;
+        let list: SegmentedList<_> = (0..len).map(|i| i % 10).collect();
+        assert_eq!(list.position(|&v| v == 3), Some(3));
+        assert_eq!(list.rposition(|&v| v == 3), Some(BLOCK_STARTS[3] - 3));
+        assert_eq!(list.position(|&v| v == 10), None);
+        assert_eq!(list.rposition(|&v| v == 10), None);
+
+        let mut list = list;
+        list[len - 1] = 42;
+        list[len - 2] = 42;
+        assert_eq!(list.position(|&v| v == 42), Some(len - 2));
+        assert_eq!(list.rposition(|&v| v == 42), Some(len - 1));
+        assert_eq!(list.rposition(|&v| v == 0), Some(BLOCK_STARTS[3] - 6));
+        assert_eq!(list.rposition(|&v| v == 6), Some(len - 3));
     }
 
     #[test]
-    fn drop_safety_test() {
-        // Counter for tracking drops
-        struct DropCounter<'a>(&'a RefCell<u32>);
-        impl<'a> Drop for DropCounter<'a> {
-            fn drop(&mut self) {
-                *self.0.borrow_mut() += 1;
-            }
+    fn windows_match_slice_windows() {
+        let len = BLOCK_STARTS[3] + 2;
+        let list: SegmentedList<_> = (0..len).collect();
+        let vec: Vec<_> = (0..len).collect();
+        for size in [1, 2, 3, START_SIZE, START_SIZE + 1, len - 1, len, len + 1] {
+            let windows = list.windows(size);
+            assert_eq!(windows.len(), vec.windows(size).len());
+            assert!(
+                windows
+                    .map(|w| w.iter().copied().collect::<Vec<_>>())
+                    .eq(vec.windows(size).map(<[usize]>::to_vec))
+            );
+            assert!(
+                list.windows(size)
+                    .rev()
+                    .map(|w| w.iter().sum::<usize>())
+                    .eq(vec.windows(size).rev().map(|w| w.iter().sum::<usize>()))
+            );
         }
 
-        let counter = Rc::new(RefCell::new(0));
-        {
-            let mut list: SegmentedList<DropCounter> = SegmentedList::new();
-            for _ in 0..50 {
-                list.push(DropCounter(&counter));
-            }
-            // consuming the list should drop all elements exactly once
-            list.to_vec();
+        // moving average over a window straddling the first block boundary
+        let window = list.windows(4).nth(START_SIZE - 2).unwrap();
+        assert_eq!(window.iter().sum::<usize>() as f64 / 4.0, 7.5);
+    }
+
+    #[test]
+    #[should_panic(expected = "window size must be non-zero")]
+    fn windows_panics_on_zero_size() {
+        let list: SegmentedList<_> = (0..4).collect();
+        list.windows(0);
+    }
+
+    #[test]
+    fn compute_segmented_idx_rejects_len() {
+        let mut list: SegmentedList<_> = (0..START_SIZE).collect();
+        assert_eq!(list.compute_segmented_idx(list.len()), None);
+        assert_eq!(
+            list.compute_segmented_idx(list.len() - 1),
+            Some(SegmentedIdx(0, START_SIZE - 1))
+        );
+        // the first slot of the second block is allocated once reserved, but not initialized
+        list.reserve(1);
+        assert_eq!(list.compute_segmented_idx(START_SIZE), None);
+    }
+
+    #[test]
+    fn chunks_match_slice_chunks() {
+        let len = BLOCK_STARTS[3] + 2;
+        let list: SegmentedList<_> = (0..len).collect();
+        let vec: Vec<_> = (0..len).collect();
+        for size in [1, 3, START_SIZE, START_SIZE + 1, len - 1, len, len + 1] {
+            let chunks = list.chunks(size);
+            assert_eq!(chunks.len(), vec.chunks(size).len());
+            assert!(
+                chunks
+                    .map(|c| c.iter().copied().collect::<Vec<_>>())
+                    .eq(vec.chunks(size).map(<[usize]>::to_vec))
+            );
+            assert!(
+                list.chunks(size)
+                    .rev()
+                    .map(|c| c.iter().copied().collect::<Vec<_>>())
+                    .eq(vec.chunks(size).rev().map(<[usize]>::to_vec))
+            );
         }
-        assert_eq!(*counter.borrow(), 50);
+
+        let mut chunks = list.chunks(START_SIZE * 3);
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(
+            chunks.next_back().map(|c| c.len()),
+            Some(len % (START_SIZE * 3))
+        );
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks.next().map(|c| c[START_SIZE]), Some(START_SIZE));
+        assert_eq!(chunks.next().map(|c| c.len()), Some(START_SIZE * 3));
+        assert_eq!(chunks.len(), 0);
+        assert!(chunks.next().is_none());
+
+        let empty: SegmentedList<u8> = SegmentedList::new();
+        assert_eq!(empty.chunks(4).len(), 0);
     }
 
     #[test]
-    fn random_values_across_blocks() {
-        let mut list = SegmentedList::new();
-        // Push sparse and varied values
-        for i in (0..(START_SIZE * 5)).rev() {
-            // reverse order for variety
-            list.push(i * 3);
+    #[should_panic(expected = "chunk size must be non-zero")]
+    fn chunks_panics_on_zero_size() {
+        let list: SegmentedList<_> = (0..4).collect();
+        list.chunks(0);
+    }
+
+    #[test]
+    fn block_math_matches_layout_for_32_bit_width() {
+        // the same msb computation idx_to_block_idx does, on a 32 bit wide integer
+        fn block_of_u32(idx: u32) -> usize {
+            if idx < START_SIZE as u32 {
+                return 0;
+            }
+            let adjusted = idx + START_SIZE as u32;
+            (u32::BITS - 1 - adjusted.leading_zeros() - START_SIZE.trailing_zeros()) as usize
         }
-        let vec = list.to_vec();
-        for (idx, val) in vec.iter().enumerate() {
-            assert_eq!(*val, (START_SIZE * 5 - 1 - idx) * 3);
+
+        let list: SegmentedList<u8> = SegmentedList::new();
+        for (block, &start) in BLOCK_STARTS.iter().enumerate() {
+            let end = start + (START_SIZE << block);
+            for idx in [start, start + 1, end - 1] {
+                let idx32 = u32::try_from(idx).expect("layout fits 32 bit indexes");
+                assert_eq!(block_of_u32(idx32), block, "idx {idx}");
+                assert_eq!(list.idx_to_block_idx(idx), SegmentedIdx(block, idx - start));
+            }
         }
     }
 
     #[test]
-    fn stress_test_large_fill() {
-        let mut list = SegmentedList::new();
-        let count = START_SIZE * 50; // large, spans many blocks
-        for i in 0..count {
-            list.push(i);
+    fn binary_search_by_insertion_points_keep_order() {
+        // sorted descending, with a run of equal keys spanning the first block boundary
+        let mut list: SegmentedList<u32> = (0..START_SIZE as u32 - 2)
+            .map(|i| 100 - i)
+            .chain([50; 6])
+            .chain((0..BLOCK_STARTS[2] as u32).map(|i| 40 - i.min(40)))
+            .collect();
+        let by_desc = |target: u32| move |v: &u32| target.cmp(v);
+
+        let found = list.binary_search_by(by_desc(50)).unwrap();
+        assert!((START_SIZE - 2..START_SIZE + 4).contains(&found));
+        assert_eq!(list[found], 50);
+
+        for target in [101, 75, 45, 0] {
+            let Err(at) = list.binary_search_by(by_desc(target)) else {
+                panic!("{target} is not in the list");
+            };
+            list.insert(at, target);
+            assert!(list.is_sorted_by(|a, b| a >= b), "after inserting {target}");
         }
-        let vec = list.to_vec();
-        assert_eq!(vec.len(), count);
-        assert_eq!(vec[count - 1], count - 1);
-        assert_eq!(vec[0], 0);
+        assert_eq!(list.first(), Some(&101));
     }
 
     #[test]
-    fn capacity_and_is_empty_work() {
-        let mut list = SegmentedList::new();
-        assert_eq!(list.capacity(), 8);
-        assert!(list.is_empty());
+    fn try_push_rejects_past_max_capacity() {
+        assert_eq!(MAX_CAPACITY, START_SIZE * ((1 << BLOCK_COUNT) - 1));
 
-        list.push(10);
-        assert!(!list.is_empty());
-        assert!(list.capacity() >= 1);
+        // all blocks are mapped but never touched, u8 has no destructor and only the slots
+        // written below are read, thus the uninitialized prefix is never observed
+        let mut list: SegmentedList<u8> = SegmentedList::new();
+        list.grow_to(MAX_CAPACITY);
+        list.set_len(MAX_CAPACITY - 1);
+        assert_eq!(list.try_push(42), Ok(()));
+        assert_eq!(list.len(), MAX_CAPACITY);
+        assert_eq!(list.capacity(), MAX_CAPACITY);
+        assert_eq!(list.last(), Some(&42));
+
+        let err = list.try_push(43).unwrap_err();
+        assert_eq!(err.into_inner(), 43);
+        assert_eq!(list.len(), MAX_CAPACITY);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| list.push(43)));
+        assert!(result.is_err());
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| list.reserve(1)));
+        assert!(result.is_err());
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| list.insert(0, 1)));
+        assert!(result.is_err());
+        assert_eq!(list.len(), MAX_CAPACITY);
+
+        assert_eq!(list.pop(), Some(42));
+        assert_eq!(list.try_push(44), Ok(()));
+        assert_eq!(list[MAX_CAPACITY - 1], 44);
+        assert!(list.try_push(45).is_err());
     }
 
     #[test]
-    fn first_and_last_work() {
-        let mut list = SegmentedList::new();
-        assert!(list.first().is_none());
-        assert!(list.last().is_none());
+    fn searches_stop_at_the_first_match() {
+        #[derive(Debug)]
+        struct Counted<'a>(usize, &'a RefCell<usize>);
+        impl PartialEq for Counted<'_> {
+            fn eq(&self, other: &Self) -> bool {
+                *self.1.borrow_mut() += 1;
+                self.0 == other.0
+            }
+        }
 
-        list.push(1);
-        list.push(2);
-        list.push(3);
-        assert_eq!(list.len(), 3);
+        let comparisons = RefCell::new(0);
+        let len = BLOCK_STARTS[4];
+        let list: SegmentedList<_> = (0..len).map(|i| Counted(i, &comparisons)).collect();
 
-        assert_eq!(list.first(), Some(&1));
-        assert_eq!(list.last(), Some(&3));
+        assert!(list.contains(&Counted(START_SIZE + 1, &comparisons)));
+        assert_eq!(*comparisons.borrow(), START_SIZE + 2);
+        *comparisons.borrow_mut() = 0;
+        assert!(!list.contains(&Counted(len, &comparisons)));
+        assert_eq!(*comparisons.borrow(), len);
 
-        *list.first_mut().unwrap() = 10;
-        *list.last_mut().unwrap() = 30;
+        let mut calls = 0;
+        let found = list.position(|v| {
+            calls += 1;
+            v.0 % START_SIZE == START_SIZE - 1
+        });
+        assert_eq!((found, calls), (Some(START_SIZE - 1), START_SIZE));
 
-        assert_eq!(list.first(), Some(&10));
-        assert_eq!(list[1], 2);
-        assert_eq!(list.last(), Some(&30));
+        let mut calls = 0;
+        let found = list.rposition(|v| {
+            calls += 1;
+            v.0 < BLOCK_STARTS[3]
+        });
+        assert_eq!(found, Some(BLOCK_STARTS[3] - 1));
+        assert_eq!(calls, len - BLOCK_STARTS[3] + 1);
     }
 
     #[test]
-    fn clear_resets_len_and_drops_items() {
-        struct DropCounter<'a>(&'a RefCell<u32>);
-        impl<'a> Drop for DropCounter<'a> {
-            fn drop(&mut self) {
-                *self.0.borrow_mut() += 1;
-            }
-        }
-        let counter = Rc::new(RefCell::new(0));
-        let mut list: SegmentedList<DropCounter> = SegmentedList::new();
+    fn split_off_moves_the_tail_once() {
+        let dropped = RefCell::new(Vec::new());
+        let len = BLOCK_STARTS[3] + 3;
+        let mut list: SegmentedList<_> = (0..len).map(|i| DropCounter(i, &dropped)).collect();
+        // mid block 1, the tail spans blocks 1 to 3
+        let at = START_SIZE + 3;
+        let tail = list.split_off(at);
+        assert_eq!(list.len(), at);
+        assert_eq!(tail.len(), len - at);
+        assert!(list.iter().map(|v| v.0).eq(0..at));
+        assert!(tail.iter().map(|v| v.0).eq(at..len));
+        assert!(dropped.borrow().is_empty());
 
-        for _ in 0..10 {
-            list.push(DropCounter(&counter));
-        }
-        assert_eq!(list.len(), 10);
-        list.clear();
-        assert_eq!(list.len(), 0);
-        assert_eq!(*counter.borrow(), 10);
-        assert!(list.is_empty());
+        list.push(DropCounter(len, &dropped));
+        drop(tail);
+        assert_eq!(*dropped.borrow(), (at..len).collect::<Vec<_>>());
+        drop(list);
+        let mut all = dropped.take();
+        all.sort();
+        assert_eq!(all, (0..=len).collect::<Vec<_>>());
     }
 
     #[test]
-    fn extend_trait_adds_items() {
-        let mut list = SegmentedList::new();
-        list.extend(vec![1, 2, 3]);
-        assert_eq!(list.len(), 3);
-        assert_eq!(list.to_vec(), vec![1, 2, 3]);
+    fn split_off_at_the_ends() {
+        let mut list: SegmentedList<_> = (0..BLOCK_STARTS[2]).map(|i| i.to_string()).collect();
+        let empty = list.split_off(list.len());
+        assert!(empty.is_empty());
+        assert_eq!(list.len(), BLOCK_STARTS[2]);
+
+        let everything = list.split_off(0);
+        assert!(list.is_empty());
+        assert!(
+            everything
+                .iter()
+                .enumerate()
+                .all(|(i, v)| *v == i.to_string())
+        );
+        list.push("again".into());
+        assert_eq!(list, ["again"]);
+
+        // at a block boundary
+        let mut list: SegmentedList<_> = (0..BLOCK_STARTS[3]).collect();
+        let tail = list.split_off(BLOCK_STARTS[2]);
+        assert_eq!(list.segments().count(), 2);
+        assert!(tail.iter().copied().eq(BLOCK_STARTS[2]..BLOCK_STARTS[3]));
     }
 
     #[test]
-    fn from_iterator_constructs_list() {
-        let list: SegmentedList<_> = (0..5).collect();
-        assert_eq!(list.len(), 5);
-        assert_eq!(list.to_vec(), (0..5).collect::<Vec<_>>());
+    #[should_panic(expected = "`at` split index (is 5) should be <= len (is 4)")]
+    fn split_off_panics_past_len() {
+        let mut list: SegmentedList<_> = (0..4).collect();
+        list.split_off(5);
     }
 }